@@ -0,0 +1,83 @@
+use std::f64::consts::PI;
+
+/// Wrap an angle into (-PI, PI]
+pub fn angle_normalize(a: f64) -> f64 {
+    let mut a = a % (2.0 * PI);
+    if a <= -PI {
+        a += 2.0 * PI;
+    } else if a > PI {
+        a -= 2.0 * PI;
+    }
+    a
+}
+
+/// Signed shortest rotation taking `from` to `to`
+pub fn angle_diff(from: f64, to: f64) -> f64 {
+    angle_normalize(to - from)
+}
+
+/// A gun's field of fire, in radians relative to the hull.
+/// Uses the same convention as the gun dials: 0 points towards the
+/// back of the ship, PI towards the front.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FiringArc {
+    pub centre: f64,
+    pub half_width: f64,
+}
+
+impl FiringArc {
+    pub fn new(centre: f64, half_width: f64) -> FiringArc {
+        FiringArc {
+            centre: angle_normalize(centre),
+            half_width: half_width.max(0.0).min(PI),
+        }
+    }
+    pub fn contains(&self, a: f64) -> bool {
+        angle_diff(self.centre, a).abs() <= self.half_width + 1e-9
+    }
+    pub fn clamp(&self, a: f64) -> f64 {
+        let d = angle_diff(self.centre, a);
+        if d.abs() <= self.half_width {
+            angle_normalize(a)
+        } else {
+            angle_normalize(self.centre + self.half_width * d.signum())
+        }
+    }
+    pub fn start(&self) -> f64 {
+        self.centre - self.half_width
+    }
+    pub fn end(&self) -> f64 {
+        self.centre + self.half_width
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn normalize() {
+        assert_eq!(angle_normalize(0.0), 0.0);
+        assert_eq!(angle_normalize(PI), PI);
+        assert_eq!(angle_normalize(-PI), PI);
+        assert!((angle_normalize(3.0 * PI) - PI).abs() < 1e-9);
+    }
+    #[test]
+    fn contains() {
+        let a = FiringArc::new(PI, PI / 2.0);
+        assert!(a.contains(PI));
+        assert!(a.contains(-PI));
+        assert!(a.contains(PI / 2.0));
+        assert!(!a.contains(0.0));
+        assert!(!a.contains(PI / 4.0));
+    }
+    #[test]
+    fn clamp() {
+        let a = FiringArc::new(0.0, PI / 4.0);
+        assert_eq!(a.clamp(0.1), 0.1);
+        assert_eq!(a.clamp(PI / 2.0), PI / 4.0);
+        assert_eq!(a.clamp(-PI / 2.0), -PI / 4.0);
+        let b = FiringArc::new(PI, PI / 4.0);
+        assert!((b.clamp(PI / 2.0) - 3.0 * PI / 4.0).abs() < 1e-9);
+        assert!((b.clamp(-PI / 2.0) + 3.0 * PI / 4.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,143 @@
+use vecmath::*;
+use collisions::*;
+
+/// What happens to things that reach the edge of the arena
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Boundary {
+    /// stop at the edge
+    Clamp,
+    /// come back in on the opposite side
+    Wrap,
+    /// bounce off the edge
+    Bounce,
+    /// leaving the map takes you out of the fight
+    Disengage,
+}
+
+/// The playfield, from (0, 0) to (w, h) in arena coordinates
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Arena {
+    pub w: f64,
+    pub h: f64,
+    pub boundary: Boundary,
+}
+
+impl Arena {
+    pub fn new(w: f64, h: f64, boundary: Boundary) -> Arena {
+        Arena { w, h, boundary }
+    }
+    pub fn size(&self) -> Vector2<f64> {
+        [self.w, self.h]
+    }
+    pub fn contains(&self, p: Vector2<f64>) -> bool {
+        p[0] >= 0.0 && p[0] <= self.w && p[1] >= 0.0 && p[1] <= self.h
+    }
+    /// Apply the boundary to something at `pos` moving at `vel`.
+    /// None means it has left the field for good.
+    pub fn confine(
+        &self,
+        pos: Vector2<f64>,
+        vel: Vector2<f64>,
+    ) -> Option<(Vector2<f64>, Vector2<f64>)> {
+        if self.contains(pos) {
+            return Some((pos, vel));
+        }
+        let size = self.size();
+        let (mut pos, mut vel) = (pos, vel);
+        match self.boundary {
+            Boundary::Clamp => {
+                for i in 0..2 {
+                    if pos[i] < 0.0 || pos[i] > size[i] {
+                        pos[i] = pos[i].max(0.0).min(size[i]);
+                        vel[i] = 0.0;
+                    }
+                }
+            }
+            Boundary::Wrap => {
+                for i in 0..2 {
+                    pos[i] = ((pos[i] % size[i]) + size[i]) % size[i];
+                }
+            }
+            Boundary::Bounce => {
+                for i in 0..2 {
+                    if pos[i] < 0.0 {
+                        pos[i] = -pos[i];
+                        vel[i] = vel[i].abs();
+                    } else if pos[i] > size[i] {
+                        pos[i] = 2.0 * size[i] - pos[i];
+                        vel[i] = -vel[i].abs();
+                    }
+                }
+            }
+            Boundary::Disengage => return None,
+        }
+        Some((pos, vel))
+    }
+    /// Like `confine`, but a projectile that would be stopped dead at the edge
+    /// is removed instead
+    pub fn confine_projectile(
+        &self,
+        pos: Vector2<f64>,
+        vel: Vector2<f64>,
+    ) -> Option<(Vector2<f64>, Vector2<f64>)> {
+        if self.boundary == Boundary::Clamp && !self.contains(pos) {
+            None
+        } else {
+            self.confine(pos, vel)
+        }
+    }
+    /// How far a ray from `origin` along unit `dir` can go, up to `range`,
+    /// before it leaves the field. Rays stop at the edge whatever the boundary.
+    pub fn clip_ray(&self, origin: Vector2<f64>, dir: Vector2<f64>, range: f64) -> f64 {
+        // come back from the far end; where that enters the field is where we left it
+        let end = vec2_add(origin, vec2_scale(dir, range));
+        let field = [0.0, 0.0, self.w, self.h];
+        match ray_box(end, vec2_scale(dir, -1.0), field) {
+            Some(t) => (range - t).max(0.0),
+            None => 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn arena(b: Boundary) -> Arena {
+        Arena::new(10.0, 10.0, b)
+    }
+    #[test]
+    fn inside() {
+        let all = [Boundary::Clamp, Boundary::Wrap, Boundary::Bounce, Boundary::Disengage];
+        for &b in all.iter() {
+            let a = arena(b);
+            assert_eq!(a.confine([5.0, 5.0], [1.0, 1.0]), Some(([5.0, 5.0], [1.0, 1.0])));
+        }
+    }
+    #[test]
+    fn clamp() {
+        let a = arena(Boundary::Clamp);
+        assert_eq!(a.confine([12.0, 5.0], [1.0, 1.0]), Some(([10.0, 5.0], [0.0, 1.0])));
+        assert_eq!(a.confine_projectile([12.0, 5.0], [1.0, 1.0]), None);
+    }
+    #[test]
+    fn wrap() {
+        let a = arena(Boundary::Wrap);
+        assert_eq!(a.confine([12.0, -1.0], [1.0, 1.0]), Some(([2.0, 9.0], [1.0, 1.0])));
+    }
+    #[test]
+    fn bounce() {
+        let a = arena(Boundary::Bounce);
+        assert_eq!(a.confine([12.0, -1.0], [1.0, -1.0]), Some(([8.0, 1.0], [-1.0, 1.0])));
+    }
+    #[test]
+    fn disengage() {
+        assert_eq!(arena(Boundary::Disengage).confine([12.0, 5.0], [1.0, 1.0]), None);
+    }
+    #[test]
+    fn clip_ray() {
+        let a = arena(Boundary::Wrap);
+        assert_eq!(a.clip_ray([5.0, 5.0], [1.0, 0.0], 3.0), 3.0);
+        assert_eq!(a.clip_ray([5.0, 5.0], [1.0, 0.0], 20.0), 5.0);
+        assert_eq!(a.clip_ray([5.0, 2.0], [0.0, -1.0], 20.0), 2.0);
+    }
+}
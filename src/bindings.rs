@@ -0,0 +1,402 @@
+use piston_window::{Button, Key};
+
+/// where bindings are saved between games
+pub const CONFIG_FILE: &'static str = "bindings.cfg";
+
+/// Something a player can ask for, whatever button they use to ask
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// push the selected ships along their bow
+    Thrust,
+    /// push them back along their stern
+    Astern,
+    /// bring the ordered heading round to port or starboard
+    TurnLeft,
+    TurnRight,
+    FireFront,
+    FireBack,
+    /// toggle the camera following the ship the panel shows
+    Follow,
+    /// step the throttle
+    Faster,
+    Slower,
+    AllStop,
+    FullAhead,
+    FullReverse,
+    EndTurn,
+    /// open or close the rebinding screen
+    Rebind,
+}
+
+pub const ACTIONS: [Action; 14] = [
+    Action::Thrust,
+    Action::Astern,
+    Action::TurnLeft,
+    Action::TurnRight,
+    Action::FireFront,
+    Action::FireBack,
+    Action::Follow,
+    Action::Faster,
+    Action::Slower,
+    Action::AllStop,
+    Action::FullAhead,
+    Action::FullReverse,
+    Action::EndTurn,
+    Action::Rebind,
+];
+
+impl Action {
+    /// How the action is written in the config file
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Action::Thrust => "thrust",
+            Action::Astern => "astern",
+            Action::TurnLeft => "turn_left",
+            Action::TurnRight => "turn_right",
+            Action::FireFront => "fire_front",
+            Action::FireBack => "fire_back",
+            Action::Follow => "follow",
+            Action::Faster => "faster",
+            Action::Slower => "slower",
+            Action::AllStop => "all_stop",
+            Action::FullAhead => "full_ahead",
+            Action::FullReverse => "full_reverse",
+            Action::EndTurn => "end_turn",
+            Action::Rebind => "rebind",
+        }
+    }
+    pub fn from_name(name: &str) -> Option<Action> {
+        ACTIONS.iter().cloned().find(|a| a.name() == name)
+    }
+}
+
+const KEY_NAMES: [(&'static str, Key); 70] = [
+    ("A", Key::A),
+    ("B", Key::B),
+    ("C", Key::C),
+    ("D", Key::D),
+    ("E", Key::E),
+    ("F", Key::F),
+    ("G", Key::G),
+    ("H", Key::H),
+    ("I", Key::I),
+    ("J", Key::J),
+    ("K", Key::K),
+    ("L", Key::L),
+    ("M", Key::M),
+    ("N", Key::N),
+    ("O", Key::O),
+    ("P", Key::P),
+    ("Q", Key::Q),
+    ("R", Key::R),
+    ("S", Key::S),
+    ("T", Key::T),
+    ("U", Key::U),
+    ("V", Key::V),
+    ("W", Key::W),
+    ("X", Key::X),
+    ("Y", Key::Y),
+    ("Z", Key::Z),
+    ("0", Key::D0),
+    ("1", Key::D1),
+    ("2", Key::D2),
+    ("3", Key::D3),
+    ("4", Key::D4),
+    ("5", Key::D5),
+    ("6", Key::D6),
+    ("7", Key::D7),
+    ("8", Key::D8),
+    ("9", Key::D9),
+    ("Up", Key::Up),
+    ("Down", Key::Down),
+    ("Left", Key::Left),
+    ("Right", Key::Right),
+    ("Space", Key::Space),
+    ("Return", Key::Return),
+    ("Tab", Key::Tab),
+    ("Backspace", Key::Backspace),
+    ("LShift", Key::LShift),
+    ("RShift", Key::RShift),
+    ("LCtrl", Key::LCtrl),
+    ("RCtrl", Key::RCtrl),
+    ("LAlt", Key::LAlt),
+    ("RAlt", Key::RAlt),
+    ("Insert", Key::Insert),
+    ("Delete", Key::Delete),
+    ("Home", Key::Home),
+    ("End", Key::End),
+    ("PageUp", Key::PageUp),
+    ("PageDown", Key::PageDown),
+    ("Comma", Key::Comma),
+    ("Period", Key::Period),
+    ("Slash", Key::Slash),
+    ("Semicolon", Key::Semicolon),
+    ("Minus", Key::Minus),
+    ("Equals", Key::Equals),
+    ("F1", Key::F1),
+    ("F2", Key::F2),
+    ("F3", Key::F3),
+    ("F4", Key::F4),
+    ("F5", Key::F5),
+    ("F6", Key::F6),
+    ("F7", Key::F7),
+    ("F8", Key::F8),
+];
+
+/// How a button is written in the config file
+pub fn button_name(b: &Button) -> Option<&'static str> {
+    match *b {
+        Button::Keyboard(k) => KEY_NAMES.iter().find(|&&(_, key)| key == k).map(|&(n, _)| n),
+        _ => None,
+    }
+}
+
+pub fn button_from_name(name: &str) -> Option<Button> {
+    KEY_NAMES
+        .iter()
+        .find(|&&(n, _)| n == name)
+        .map(|&(_, k)| Button::Keyboard(k))
+}
+
+/// Which buttons ask for which actions. An action can have any number of
+/// buttons, and a button can ask for several actions at once.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bindings {
+    bound: Vec<(Action, Button)>,
+    /// the action the next button pressed will be bound to
+    listening: Option<Action>,
+}
+
+impl Bindings {
+    pub fn new() -> Bindings {
+        Bindings {
+            bound: Vec::new(),
+            listening: None,
+        }
+    }
+    pub fn bind(&mut self, a: Action, b: Button) {
+        if !self.bound.contains(&(a, b)) {
+            self.bound.push((a, b));
+        }
+    }
+    /// Remove every binding for `a`
+    pub fn clear(&mut self, a: Action) {
+        self.bound.retain(|&(x, _)| x != a);
+    }
+    /// The actions `b` asks for
+    pub fn actions(&self, b: &Button) -> Vec<Action> {
+        self.bound
+            .iter()
+            .filter(|&&(_, x)| x == *b)
+            .map(|&(a, _)| a)
+            .collect()
+    }
+    /// The buttons bound to `a`
+    pub fn buttons(&self, a: Action) -> Vec<Button> {
+        self.bound
+            .iter()
+            .filter(|&&(x, _)| x == a)
+            .map(|&(_, b)| b)
+            .collect()
+    }
+    /// Bind the next button pressed to `a`, or stop waiting with None
+    pub fn listen(&mut self, a: Option<Action>) {
+        self.listening = a;
+    }
+    pub fn listening(&self) -> Option<Action> {
+        self.listening
+    }
+    /// If waiting for a button, bind `b` and stop waiting. True if `b` was used up.
+    /// Only keys the config file can name are bound, and mouse and controller
+    /// buttons are left to do what they usually do.
+    pub fn capture(&mut self, b: Button) -> bool {
+        let a = match self.listening {
+            Some(a) => a,
+            None => return false,
+        };
+        match b {
+            Button::Keyboard(_) => {
+                if button_name(&b).is_some() {
+                    self.bind(a, b);
+                    self.listening = None;
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn defaults(keys: &[(Action, Key)]) -> Bindings {
+    let mut b = Bindings::new();
+    for &(a, k) in keys.iter() {
+        b.bind(a, Button::Keyboard(k));
+    }
+    b
+}
+
+/// The whole keyboard to one player
+pub fn solo_bindings() -> Bindings {
+    defaults(&[
+        (Action::Thrust, Key::W),
+        (Action::Astern, Key::S),
+        (Action::TurnLeft, Key::A),
+        (Action::TurnRight, Key::D),
+        (Action::FireFront, Key::Space),
+        (Action::FireBack, Key::Space),
+        (Action::Follow, Key::F),
+        (Action::Faster, Key::Up),
+        (Action::Slower, Key::Down),
+        (Action::AllStop, Key::X),
+        (Action::FullAhead, Key::Z),
+        (Action::FullReverse, Key::C),
+        (Action::EndTurn, Key::Return),
+        (Action::Rebind, Key::F1),
+    ])
+}
+
+/// The left hand side of a shared keyboard
+pub fn left_bindings() -> Bindings {
+    defaults(&[
+        (Action::Thrust, Key::W),
+        (Action::Astern, Key::S),
+        (Action::TurnLeft, Key::A),
+        (Action::TurnRight, Key::D),
+        (Action::FireFront, Key::Space),
+        (Action::FireBack, Key::Space),
+        (Action::Follow, Key::F),
+        (Action::Faster, Key::E),
+        (Action::Slower, Key::Q),
+        (Action::AllStop, Key::X),
+        (Action::FullAhead, Key::Z),
+        (Action::FullReverse, Key::C),
+        (Action::Rebind, Key::F1),
+    ])
+}
+
+/// The right hand side of a shared keyboard
+pub fn right_bindings() -> Bindings {
+    defaults(&[
+        (Action::Thrust, Key::Up),
+        (Action::Astern, Key::Down),
+        (Action::TurnLeft, Key::Left),
+        (Action::TurnRight, Key::Right),
+        (Action::FireFront, Key::Return),
+        (Action::FireBack, Key::Return),
+        (Action::Follow, Key::RShift),
+        (Action::Faster, Key::PageUp),
+        (Action::Slower, Key::PageDown),
+        (Action::AllStop, Key::End),
+        (Action::FullAhead, Key::Home),
+        (Action::FullReverse, Key::Insert),
+        (Action::Rebind, Key::F2),
+    ])
+}
+
+/// Read a config file: a `[name]` line starts the bindings for the seat
+/// of that name, then each `action = button, button` line binds an action.
+/// Blank lines and lines starting with `#` are skipped.
+pub fn parse_config(text: &str) -> Result<Vec<(String, Bindings)>, String> {
+    let mut seats: Vec<(String, Bindings)> = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            seats.push((line[1..line.len() - 1].trim().to_string(), Bindings::new()));
+            continue;
+        }
+        let mut parts = line.splitn(2, '=');
+        let (name, buttons) = match (parts.next(), parts.next()) {
+            (Some(name), Some(buttons)) => (name.trim(), buttons),
+            _ => return Err(format!("line {}: expected `action = buttons`", n + 1)),
+        };
+        let a = match Action::from_name(name) {
+            Some(a) => a,
+            None => return Err(format!("line {}: unknown action {}", n + 1, name)),
+        };
+        let bindings = match seats.last_mut() {
+            Some(&mut (_, ref mut b)) => b,
+            None => return Err(format!("line {}: binding before any [seat]", n + 1)),
+        };
+        for button in buttons.split(',').map(|b| b.trim()).filter(|b| !b.is_empty()) {
+            match button_from_name(button) {
+                Some(b) => bindings.bind(a, b),
+                None => return Err(format!("line {}: unknown button {}", n + 1, button)),
+            }
+        }
+    }
+    Ok(seats)
+}
+
+/// The config file `parse_config` reads back as `seats`
+pub fn write_config(seats: &[(&str, &Bindings)]) -> String {
+    let mut out = String::new();
+    for &(name, bindings) in seats.iter() {
+        out.push_str(&format!("[{}]\n", name));
+        for a in ACTIONS.iter() {
+            let names = bindings
+                .buttons(*a)
+                .iter()
+                .filter_map(button_name)
+                .collect::<Vec<_>>();
+            if !names.is_empty() {
+                out.push_str(&format!("{} = {}\n", a.name(), names.join(", ")));
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use piston_window::MouseButton;
+    #[test]
+    fn several_each_way() {
+        let b = solo_bindings();
+        let space = Button::Keyboard(Key::Space);
+        assert_eq!(b.actions(&space), vec![Action::FireFront, Action::FireBack]);
+        let mut b = b;
+        b.bind(Action::Thrust, Button::Keyboard(Key::Up));
+        assert_eq!(
+            b.buttons(Action::Thrust),
+            vec![Button::Keyboard(Key::W), Button::Keyboard(Key::Up)]
+        );
+        b.clear(Action::Thrust);
+        assert!(b.buttons(Action::Thrust).is_empty());
+    }
+    #[test]
+    fn capture() {
+        let mut b = Bindings::new();
+        assert!(!b.capture(Button::Keyboard(Key::K)));
+        b.listen(Some(Action::Follow));
+        // clicks aren't bound, and keys the config can't name are swallowed
+        assert!(!b.capture(Button::Mouse(MouseButton::Left)));
+        assert!(b.capture(Button::Keyboard(Key::Pause)));
+        assert_eq!(b.listening(), Some(Action::Follow));
+        assert!(b.capture(Button::Keyboard(Key::K)));
+        assert_eq!(b.listening(), None);
+        assert_eq!(b.actions(&Button::Keyboard(Key::K)), vec![Action::Follow]);
+    }
+    #[test]
+    fn round_trip() {
+        let (left, right) = (left_bindings(), right_bindings());
+        let text = write_config(&[("left", &left), ("right", &right)]);
+        let seats = parse_config(&text).unwrap();
+        assert_eq!(seats, vec![("left".to_string(), left), ("right".to_string(), right)]);
+    }
+    #[test]
+    fn parse() {
+        let text = "# comment\n[solo]\nthrust = W, Up\n\nfire_front=Space\n";
+        let seats = parse_config(text).unwrap();
+        assert_eq!(seats.len(), 1);
+        assert_eq!(seats[0].0, "solo");
+        assert_eq!(seats[0].1.buttons(Action::Thrust).len(), 2);
+        assert!(parse_config("[solo]\nwarp = W\n").is_err());
+        assert!(parse_config("[solo]\nthrust = Nope\n").is_err());
+        assert!(parse_config("thrust = W\n").is_err());
+    }
+}
@@ -0,0 +1,96 @@
+use vecmath::*;
+use graphics::math::{Matrix2d, multiply, scale, translate};
+use collisions::*;
+use components::UUID;
+use common::*;
+
+const MIN_ZOOM: f64 = 0.25;
+const MAX_ZOOM: f64 = 4.0;
+
+/// Every camera looking at the arena, one per player on screen
+pub type Cameras = SharedRef<Vec<Shared<Camera>>>;
+
+/// Maps arena coordinates onto a rectangle of the screen
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    /// screen rectangle the arena is drawn into
+    pub view: BoundingBox,
+    /// arena point shown in the middle of the view
+    pub centre: Vector2<f64>,
+    /// screen pixels per arena unit
+    pub zoom: f64,
+    /// GameObject the camera keeps centred, if any
+    pub follow: Option<UUID>,
+}
+
+impl Camera {
+    pub fn new(view: BoundingBox, centre: Vector2<f64>) -> Camera {
+        Camera {
+            view: view,
+            centre: centre,
+            zoom: 1.0,
+            follow: None,
+        }
+    }
+    pub fn world_to_screen(&self, p: Vector2<f64>) -> Vector2<f64> {
+        vec2_add(bb_center(self.view), vec2_scale(vec2_sub(p, self.centre), self.zoom))
+    }
+    pub fn screen_to_world(&self, p: Vector2<f64>) -> Vector2<f64> {
+        vec2_add(self.centre, vec2_scale(vec2_sub(p, bb_center(self.view)), 1.0 / self.zoom))
+    }
+    /// Transform taking arena coordinates to the screen
+    pub fn matrix(&self) -> Matrix2d {
+        let c = bb_center(self.view);
+        multiply(
+            multiply(translate(c), scale(self.zoom, self.zoom)),
+            translate([-self.centre[0], -self.centre[1]]),
+        )
+    }
+    /// The part of the arena that is on screen
+    pub fn visible(&self) -> BoundingBox {
+        let tl = self.screen_to_world([self.view[0], self.view[1]]);
+        [tl[0], tl[1], self.view[2] / self.zoom, self.view[3] / self.zoom]
+    }
+    /// Zoom by `factor`, keeping the arena point under `screen` where it is
+    pub fn zoom_at(&mut self, screen: Vector2<f64>, factor: f64) {
+        let before = self.screen_to_world(screen);
+        self.zoom = (self.zoom * factor).max(MIN_ZOOM).min(MAX_ZOOM);
+        let after = self.screen_to_world(screen);
+        self.centre = vec2_add(self.centre, vec2_sub(before, after));
+    }
+    /// Drag the view by a distance in screen pixels
+    pub fn pan(&mut self, screen_delta: Vector2<f64>) {
+        self.centre = vec2_sub(self.centre, vec2_scale(screen_delta, 1.0 / self.zoom));
+        self.follow = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn camera() -> Camera {
+        Camera::new([100.0, 0.0, 200.0, 100.0], [50.0, 50.0])
+    }
+    #[test]
+    fn round_trip() {
+        let mut c = camera();
+        c.zoom = 2.0;
+        assert_eq!(c.world_to_screen([50.0, 50.0]), [200.0, 50.0]);
+        assert_eq!(c.world_to_screen([60.0, 50.0]), [220.0, 50.0]);
+        assert_eq!(c.screen_to_world([220.0, 50.0]), [60.0, 50.0]);
+    }
+    #[test]
+    fn zoom_keeps_point() {
+        let mut c = camera();
+        let p = c.screen_to_world([120.0, 10.0]);
+        c.zoom_at([120.0, 10.0], 2.0);
+        assert_eq!(c.screen_to_world([120.0, 10.0]), p);
+    }
+    #[test]
+    fn pan() {
+        let mut c = camera();
+        c.zoom = 2.0;
+        c.pan([10.0, 0.0]);
+        assert_eq!(c.centre, [45.0, 50.0]);
+    }
+}
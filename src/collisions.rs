@@ -23,6 +23,79 @@ pub fn collides_box(a: BoundingBox, b: BoundingBox) -> bool {
 pub fn collides_point(b: BoundingBox, p: Vector2<f64>) -> bool {
     p[0] >= bb_x0(b) && p[0] <= bb_x1(b) && p[1] >= bb_y0(b) && p[1] <= bb_y1(b) 
 }
+/// Unlike `collides_box` this also catches one box sitting entirely inside the other
+pub fn overlaps(a: BoundingBox, b: BoundingBox) -> bool {
+    bb_x0(a) <= bb_x1(b) && bb_x0(b) <= bb_x1(a) && bb_y0(a) <= bb_y1(b) && bb_y0(b) <= bb_y1(a)
+}
+pub fn bb_center(b: BoundingBox) -> Vector2<f64> {
+    [b[0] + b[2] / 2.0, b[1] + b[3] / 2.0]
+}
+/// A box of the given size centred on `p`
+pub fn bb_around(p: Vector2<f64>, w: f64, h: f64) -> BoundingBox {
+    [p[0] - w / 2.0, p[1] - h / 2.0, w, h]
+}
+/// Smallest box holding a `half[0]*2` by `half[1]*2` rectangle centred on `c`
+/// and rotated by `a` radians
+pub fn bb_rotated(c: Vector2<f64>, half: Vector2<f64>, a: f64) -> BoundingBox {
+    let (s, co) = a.sin_cos();
+    let ex = (co * half[0]).abs() + (s * half[1]).abs();
+    let ey = (s * half[0]).abs() + (co * half[1]).abs();
+    [c[0] - ex, c[1] - ey, ex * 2.0, ey * 2.0]
+}
+/// How two overlapping boxes touch
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contact {
+    /// unit vector pointing from b towards a
+    pub normal: Vector2<f64>,
+    /// how far a has to move along `normal` to stop overlapping
+    pub depth: f64,
+}
+/// Contact between `a` and `b`, separated along whichever axis overlaps least
+pub fn contact(a: BoundingBox, b: BoundingBox) -> Option<Contact> {
+    if !overlaps(a, b) {
+        return None;
+    }
+    let dx = f64::min(bb_x1(a), bb_x1(b)) - f64::max(bb_x0(a), bb_x0(b));
+    let dy = f64::min(bb_y1(a), bb_y1(b)) - f64::max(bb_y0(a), bb_y0(b));
+    let (ca, cb) = (bb_center(a), bb_center(b));
+    if dx < dy {
+        let s = if ca[0] < cb[0] { -1.0 } else { 1.0 };
+        Some(Contact {
+            normal: [s, 0.0],
+            depth: dx,
+        })
+    } else {
+        let s = if ca[1] < cb[1] { -1.0 } else { 1.0 };
+        Some(Contact {
+            normal: [0.0, s],
+            depth: dy,
+        })
+    }
+}
+/// Distance along `dir` (in multiples of `dir`) at which a ray from `origin`
+/// first enters `b`, or 0 if it starts inside
+pub fn ray_box(origin: Vector2<f64>, dir: Vector2<f64>, b: BoundingBox) -> Option<f64> {
+    let mut tmin: f64 = 0.0;
+    let mut tmax = ::std::f64::INFINITY;
+    for i in 0..2 {
+        let lo = b[i];
+        let hi = b[i] + b[i + 2];
+        if dir[i].abs() < 1e-12 {
+            if origin[i] < lo || origin[i] > hi {
+                return None;
+            }
+        } else {
+            let t1 = (lo - origin[i]) / dir[i];
+            let t2 = (hi - origin[i]) / dir[i];
+            tmin = f64::max(tmin, f64::min(t1, t2));
+            tmax = f64::min(tmax, f64::max(t1, t2));
+            if tmin > tmax {
+                return None;
+            }
+        }
+    }
+    Some(tmin)
+}
 #[cfg(test)]
 mod test {
     use super::*;
@@ -38,4 +111,32 @@ mod test {
     fn test_point(){
         assert!(collides_point([0.0,0.0,10.0,10.0],[1.0,1.0]));
     }
+    #[test]
+    fn test_overlaps(){
+        assert!(overlaps([0.0,0.0,10.0,10.0],[2.0,2.0,1.0,1.0]));
+        assert!(overlaps([2.0,2.0,1.0,1.0],[0.0,0.0,10.0,10.0]));
+        assert!(!overlaps([0.0,0.0,1.0,1.0],[2.0,2.0,1.0,1.0]));
+    }
+    #[test]
+    fn test_rotated(){
+        assert_eq!(bb_rotated([0.0,0.0],[1.0,3.0],0.0),[-1.0,-3.0,2.0,6.0]);
+        let b = bb_rotated([0.0,0.0],[1.0,3.0],::std::f64::consts::PI / 2.0);
+        assert!((b[2] - 6.0).abs() < 1e-9 && (b[3] - 2.0).abs() < 1e-9);
+    }
+    #[test]
+    fn test_contact(){
+        assert_eq!(contact([0.0,0.0,1.0,1.0],[2.0,2.0,1.0,1.0]),None);
+        assert_eq!(contact([0.0,0.0,10.0,10.0],[8.0,1.0,10.0,8.0]),
+                   Some(Contact{normal:[-1.0,0.0],depth:2.0}));
+        assert_eq!(contact([0.0,9.0,10.0,10.0],[0.0,0.0,10.0,10.0]),
+                   Some(Contact{normal:[0.0,1.0],depth:1.0}));
+    }
+    #[test]
+    fn test_ray(){
+        let b = [10.0,-1.0,2.0,2.0];
+        assert_eq!(ray_box([0.0,0.0],[1.0,0.0],b),Some(10.0));
+        assert_eq!(ray_box([0.0,0.0],[-1.0,0.0],b),None);
+        assert_eq!(ray_box([0.0,5.0],[1.0,0.0],b),None);
+        assert_eq!(ray_box([11.0,0.0],[1.0,0.0],b),Some(0.0));
+    }
 }
\ No newline at end of file
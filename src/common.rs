@@ -1,8 +1,23 @@
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 pub type Shared<T> = Rc<Cell<T>>;
 #[inline]
 pub fn shared<T>(t: T) -> Shared<T> {
     Rc::new(Cell::new(t))
-}
\ No newline at end of file
+}
+/// Modify the value in a `Shared` in place, returning whatever `f` returns
+#[inline]
+pub fn update<T: Copy, R, F: FnOnce(&mut T) -> R>(s: &Shared<T>, f: F) -> R {
+    let mut t = s.get();
+    let r = f(&mut t);
+    s.set(t);
+    r
+}
+
+/// Like `Shared`, for values that aren't `Copy`
+pub type SharedRef<T> = Rc<RefCell<T>>;
+#[inline]
+pub fn shared_ref<T>(t: T) -> SharedRef<T> {
+    Rc::new(RefCell::new(t))
+}
@@ -0,0 +1,67 @@
+use piston_window::*;
+use vecmath::*;
+use components::*;
+use super::weapon::*;
+use team::*;
+
+/// seconds the beam stays on screen after firing
+const BEAM_TIME: f64 = 0.15;
+const BEAM_DAMAGE: u32 = 1;
+
+/// A hitscan laser, stopping at the edge of the arena. The hit is resolved
+/// on the first tick, after that it only lingers long enough to be seen.
+pub struct Beam {
+    shot: Shot,
+    end: Option<Vector2<f64>>,
+    life: f64,
+}
+
+impl Beam {
+    pub fn new(shot: Shot) -> Beam {
+        Beam {
+            shot: shot,
+            end: None,
+            life: BEAM_TIME,
+        }
+    }
+}
+
+impl Component for Beam {
+    fn tick(&mut self, args: &UpdateArgs, w: &mut World) {
+        match self.end {
+            None => {
+                let s = self.shot;
+                let range = w.arena().clip_ray(s.pos, s.dir, s.range);
+                let hit = w.raycast(s.pos, s.dir, range, s.owner, s.player);
+                let len = hit.map_or(range, |(_, t)| t);
+                self.end = Some(vec2_add(s.pos, vec2_scale(s.dir, len)));
+                if let Some((id, _)) = hit {
+                    w.damage(id, BEAM_DAMAGE);
+                }
+            }
+            Some(_) => {
+                self.life -= args.dt;
+                if self.life <= 0.0 {
+                    w.destroy_self();
+                }
+            }
+        }
+    }
+    fn draw(&mut self, c: Context, g: &mut G2d) {
+        if let Some(end) = self.end {
+            let p = self.shot.pos;
+            Line::new(self.shot.color, 0.5).draw(
+                [p[0], p[1], end[0], end[1]],
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+        }
+    }
+    fn owner(&self) -> Option<UUID> {
+        self.shot.owner
+    }
+    fn player(&self) -> Option<PlayerId> {
+        self.shot.player
+    }
+}
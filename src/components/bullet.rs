@@ -1,30 +1,85 @@
 use piston_window::*;
 use vecmath::*;
 use components::*;
+use super::weapon::*;
+use team::*;
+
+/// How far a bullet flies before it is spent
+pub const BASE_RANGE: f64 = 300.0;
+/// Extra range (as a fraction of BASE_RANGE) for a shooter that is standing still
+pub const STATIONARY_BONUS: f64 = 0.5;
+/// Shooters at or above this fraction of their top speed get no stationary bonus
+pub const FULL_SPEED: f64 = 1.0;
+
+const BULLET_SPEED: f64 = 2.0;
+const BULLET_DAMAGE: u32 = 1;
+
+/// Range of a shot fired by a ship moving at `speed`, as a fraction of its top speed.
+/// The bonus tapers off linearly as the shooter speeds up.
+pub fn range_for_speed(speed: f64) -> f64 {
+    let still = 1.0 - f64::min(speed.abs() / FULL_SPEED, 1.0);
+    BASE_RANGE * (1.0 + STATIONARY_BONUS * still)
+}
 
 pub struct Bullet {
-    color: [f32; 4],
+    shot: Shot,
     pos: Vector2<f64>,
     dir: Vector2<f64>,
+    range: f64,
 }
 
 impl Bullet {
-    pub fn new(color: [f32; 4], pos: Vector2<f64>, dir: Vector2<f64>) -> Bullet {
-        Bullet {color,pos,dir}
+    pub fn new(shot: Shot) -> Bullet {
+        Bullet {
+            shot: shot,
+            pos: shot.pos,
+            dir: vec2_scale(shot.dir, BULLET_SPEED),
+            range: shot.range,
+        }
     }
 }
 
 impl Component for Bullet {
-    fn tick(&mut self, args: &UpdateArgs, _: &mut World) {
-        self.pos = vec2_add(self.pos, self.dir);
+    fn tick(&mut self, args: &UpdateArgs, w: &mut World) {
+        self.range -= vec2_len(self.dir);
+        match w.arena().confine_projectile(vec2_add(self.pos, self.dir), self.dir) {
+            Some((pos, dir)) => {
+                self.pos = pos;
+                self.dir = dir;
+            }
+            None => {
+                w.destroy_self();
+                return;
+            }
+        }
+        let s = self.shot;
+        let hit = hits(w, [self.pos[0], self.pos[1], 2.0, 2.0], s.owner, s.player);
+        if let Some(&id) = hit.first() {
+            w.damage(id, BULLET_DAMAGE);
+            w.destroy_self();
+        } else if self.range <= 0.0 {
+            w.destroy_self();
+        }
     }
     fn draw(&mut self, c: Context, g: &mut G2d) {
-        rectangle(
-            self.color, // red
+        Rectangle::new(self.shot.color).draw(
             [self.pos[0], self.pos[1], 2.0, 2.0],
+            &c.draw_state,
             c.transform,
             g,
         );
     }
+    fn owner(&self) -> Option<UUID> {
+        self.shot.owner
+    }
+    fn player(&self) -> Option<PlayerId> {
+        self.shot.player
+    }
+    fn blip(&self) -> Option<Blip> {
+        Some(Blip {
+            kind: BlipKind::Projectile,
+            pos: self.pos,
+            color: self.shot.color,
+        })
+    }
 }
-
@@ -0,0 +1,80 @@
+use piston_window::*;
+use vecmath::*;
+use components::*;
+use collisions::*;
+use super::weapon::*;
+
+const DEBRIS_SIZE: f64 = 4.0;
+const DEBRIS_DAMAGE: u32 = 1;
+
+/// A piece of a destroyed ship. Anything that runs into it takes
+/// DEBRIS_DAMAGE and the debris is gone.
+pub struct Debris {
+    color: [f32; 4],
+    pos: Vector2<f64>,
+    /// units per second
+    vel: Vector2<f64>,
+    /// the hulk this came off, so pieces don't hit it or each other as they separate
+    owner: Option<UUID>,
+}
+
+impl Debris {
+    pub fn new(
+        color: [f32; 4],
+        pos: Vector2<f64>,
+        vel: Vector2<f64>,
+        owner: Option<UUID>,
+    ) -> Debris {
+        Debris {
+            color,
+            pos,
+            vel,
+            owner,
+        }
+    }
+}
+
+impl Component for Debris {
+    fn tick(&mut self, args: &UpdateArgs, w: &mut World) {
+        let pos = vec2_add(self.pos, vec2_scale(self.vel, args.dt));
+        match w.arena().confine_projectile(pos, self.vel) {
+            Some((pos, vel)) => {
+                self.pos = pos;
+                self.vel = vel;
+            }
+            None => {
+                w.destroy_self();
+                return;
+            }
+        }
+        let hit = hits(w, bb_around(self.pos, DEBRIS_SIZE, DEBRIS_SIZE), self.owner, None);
+        if let Some(&id) = hit.first() {
+            w.damage(id, DEBRIS_DAMAGE);
+            w.destroy_self();
+        }
+    }
+    fn hit(&mut self, _: u32, w: &mut World) {
+        w.destroy_self();
+    }
+    fn draw(&mut self, c: Context, g: &mut G2d) {
+        Rectangle::new(self.color).draw(
+            bb_around(self.pos, DEBRIS_SIZE, DEBRIS_SIZE),
+            &c.draw_state,
+            c.transform,
+            g,
+        );
+    }
+    fn collider(&self) -> Option<BoundingBox> {
+        Some(bb_around(self.pos, DEBRIS_SIZE, DEBRIS_SIZE))
+    }
+    fn owner(&self) -> Option<UUID> {
+        self.owner
+    }
+    fn blip(&self) -> Option<Blip> {
+        Some(Blip {
+            kind: BlipKind::Debris,
+            pos: self.pos,
+            color: self.color,
+        })
+    }
+}
@@ -0,0 +1,110 @@
+use piston_window::*;
+use vecmath::*;
+use components::*;
+use collisions::*;
+use super::weapon::*;
+use team::*;
+
+const FLAK_SPEED: f64 = 1.5;
+/// distance at which the shell bursts, if its range allows
+const FLAK_FUSE: f64 = 150.0;
+const FLAK_RADIUS: f64 = 25.0;
+const FLAK_DAMAGE: u32 = 1;
+/// seconds the burst stays on screen
+const BURST_TIME: f64 = 0.2;
+
+/// A shell that bursts after a set distance (or on contact),
+/// damaging everything within FLAK_RADIUS
+pub struct Flak {
+    shot: Shot,
+    pos: Vector2<f64>,
+    vel: Vector2<f64>,
+    fuse: f64,
+    burst: Option<f64>,
+}
+
+impl Flak {
+    pub fn new(shot: Shot) -> Flak {
+        Flak {
+            shot: shot,
+            pos: shot.pos,
+            vel: vec2_scale(shot.dir, FLAK_SPEED),
+            fuse: f64::min(FLAK_FUSE, shot.range),
+            burst: None,
+        }
+    }
+    fn explode(&mut self, w: &mut World) {
+        let area = bb_around(self.pos, FLAK_RADIUS * 2.0, FLAK_RADIUS * 2.0);
+        for id in hits(w, area, self.shot.owner, self.shot.player) {
+            w.damage(id, FLAK_DAMAGE);
+        }
+        self.burst = Some(BURST_TIME);
+    }
+}
+
+impl Component for Flak {
+    fn tick(&mut self, args: &UpdateArgs, w: &mut World) {
+        match self.burst {
+            Some(t) => {
+                if t <= 0.0 {
+                    w.destroy_self();
+                }
+                self.burst = Some(t - args.dt);
+            }
+            None => {
+                self.fuse -= FLAK_SPEED;
+                match w.arena().confine_projectile(vec2_add(self.pos, self.vel), self.vel) {
+                    Some((pos, vel)) => {
+                        self.pos = pos;
+                        self.vel = vel;
+                    }
+                    None => {
+                        w.destroy_self();
+                        return;
+                    }
+                }
+                let s = self.shot;
+                let contact = !hits(w, [self.pos[0], self.pos[1], 2.0, 2.0], s.owner, s.player)
+                    .is_empty();
+                if self.fuse <= 0.0 || contact {
+                    self.explode(w);
+                }
+            }
+        }
+    }
+    fn draw(&mut self, c: Context, g: &mut G2d) {
+        match self.burst {
+            Some(_) => {
+                let mut color = self.shot.color;
+                color[3] = 0.3;
+                Ellipse::new(color).draw(
+                    bb_around(self.pos, FLAK_RADIUS * 2.0, FLAK_RADIUS * 2.0),
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+            }
+            None => {
+                Rectangle::new(self.shot.color).draw(
+                    [self.pos[0], self.pos[1], 2.0, 2.0],
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                )
+            }
+        }
+    }
+    fn owner(&self) -> Option<UUID> {
+        self.shot.owner
+    }
+    fn player(&self) -> Option<PlayerId> {
+        self.shot.player
+    }
+    fn blip(&self) -> Option<Blip> {
+        Some(Blip {
+            kind: BlipKind::Projectile,
+            pos: self.pos,
+            color: self.shot.color,
+        })
+    }
+}
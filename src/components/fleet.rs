@@ -0,0 +1,189 @@
+use components::*;
+use common::*;
+use turret::*;
+use arc::*;
+use math::*;
+use vecmath::*;
+use collisions::*;
+use super::ship::Pose;
+use team::*;
+
+/// The controls of one ship, as the control panel sees them
+#[derive(Clone)]
+pub struct ShipLink {
+    pub id: UUID,
+    pub player: PlayerId,
+    /// ordered heading, radians
+    pub heading: Shared<f64>,
+    pub throttle: Shared<f64>,
+    pub front_gun: Shared<f64>,
+    pub front_arc: FiringArc,
+    pub front_state: Shared<GunState>,
+    pub back_gun: Shared<f64>,
+    pub back_arc: FiringArc,
+    pub back_state: Shared<GunState>,
+    pub pose: Shared<Pose>,
+}
+
+/// Something every selected ship is told to do at once
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Order {
+    Heading(f64),
+    Throttle(f64),
+    /// point every gun that can bear at this arena position
+    Target(Vector2<f64>),
+}
+
+impl Order {
+    fn describe(&self) -> &'static str {
+        match *self {
+            Order::Heading(_) => "change heading",
+            Order::Throttle(_) => "change throttle",
+            Order::Target(_) => "bring a gun to bear",
+        }
+    }
+}
+
+/// Try to carry out `order` on one ship; false if it can't
+fn obey(ship: &ShipLink, order: Order) -> bool {
+    let pose = ship.pose.get();
+    if !pose.alive {
+        return false;
+    }
+    match order {
+        Order::Heading(a) => ship.heading.set(a),
+        Order::Throttle(v) => ship.throttle.set(v),
+        Order::Target(p) => {
+            let v = vec2_sub(p, pose.pos);
+            // the angle a gun would need to point along v, see Ship::fire_gun
+            let a = angle_normalize(v[0].atan2(-v[1]) - vec2_angle(pose.dir));
+            let front = ship.front_arc.contains(a);
+            let back = ship.back_arc.contains(a);
+            if front {
+                ship.front_gun.set(a);
+            }
+            if back {
+                ship.back_gun.set(a);
+            }
+            return front || back;
+        }
+    }
+    true
+}
+
+/// Every ship that can take orders, and which of them are selected.
+/// Only the active player's ships can be selected, so only they take orders.
+pub struct Fleet {
+    ships: Vec<ShipLink>,
+    /// the first is the one the control panel shows
+    selected: Vec<UUID>,
+    active: PlayerId,
+}
+
+impl Fleet {
+    pub fn new() -> Fleet {
+        Fleet {
+            ships: Vec::new(),
+            selected: Vec::new(),
+            active: 0,
+        }
+    }
+    /// Add a ship; the active player's first ship is selected
+    pub fn register(&mut self, link: ShipLink) {
+        if self.selected.is_empty() && link.player == self.active {
+            self.selected.push(link.id);
+        }
+        self.ships.push(link);
+    }
+    /// Forget a ship that has left the game, moving the selection on if it had it
+    pub fn remove(&mut self, id: UUID) {
+        self.ships.retain(|s| s.id != id);
+        self.selected.retain(|&s| s != id);
+        if self.selected.is_empty() {
+            self.select_first();
+        }
+    }
+    fn select_first(&mut self) {
+        let active = self.active;
+        self.selected = self.ships
+            .iter()
+            .find(|s| s.player == active)
+            .map(|s| s.id)
+            .into_iter()
+            .collect();
+    }
+    /// Hand the controls to another player
+    pub fn set_active(&mut self, player: PlayerId) {
+        self.active = player;
+        self.select_first();
+    }
+    /// Select the active player's ship closest to `p` (in arena coordinates),
+    /// if one is within `radius` of it
+    pub fn select_at(&mut self, p: Vector2<f64>, radius: f64) {
+        let active = self.active;
+        let mut best: Option<(UUID, f64)> = None;
+        for s in self.ships.iter().filter(|s| s.player == active) {
+            let d = vec2_len(vec2_sub(s.pose.get().pos, p));
+            if d <= radius && best.map_or(true, |(_, bd)| d < bd) {
+                best = Some((s.id, d));
+            }
+        }
+        if let Some((id, _)) = best {
+            self.selected = vec![id];
+        }
+    }
+    /// Select every one of the active player's ships inside `area`
+    /// (in arena coordinates), if there are any
+    pub fn select_in(&mut self, area: BoundingBox) {
+        let active = self.active;
+        let inside = self.ships
+            .iter()
+            .filter(|s| s.player == active && collides_point(area, s.pose.get().pos))
+            .map(|s| s.id)
+            .collect::<Vec<_>>();
+        if !inside.is_empty() {
+            self.selected = inside;
+        }
+    }
+    pub fn is_selected(&self, id: UUID) -> bool {
+        self.selected.contains(&id)
+    }
+    /// Is this the selected ship the control panel shows?
+    pub fn is_primary(&self, id: UUID) -> bool {
+        self.selected.first() == Some(&id)
+    }
+    /// The selected ship the control panel shows
+    pub fn selected(&self) -> Option<ShipLink> {
+        self.selected.first().and_then(|&id| self.find(id))
+    }
+    pub fn selection(&self) -> Vec<ShipLink> {
+        self.selected.iter().filter_map(|&id| self.find(id)).collect()
+    }
+    fn find(&self, id: UUID) -> Option<ShipLink> {
+        self.ships.iter().find(|s| s.id == id).cloned()
+    }
+    /// Give `order` to every selected ship, returning those that couldn't follow it
+    pub fn order(&self, order: Order) -> Vec<UUID> {
+        self.selection()
+            .iter()
+            .filter(|s| !obey(s, order))
+            .map(|s| s.id)
+            .collect()
+    }
+}
+
+/// The event log line for a ship that couldn't follow `order`
+pub fn refusal(id: UUID, order: Order) -> String {
+    format!("ship {} can't {}", id, order.describe())
+}
+
+/// Log the ships that couldn't follow `order`. `reported` holds those already
+/// logged, so an order repeated on every drag only shows up once per ship.
+pub fn report(w: &mut World, order: Order, refused: Vec<UUID>, reported: &mut Vec<UUID>) {
+    for &id in refused.iter() {
+        if !reported.contains(&id) {
+            w.log(refusal(id, order));
+        }
+    }
+    *reported = refused;
+}
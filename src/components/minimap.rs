@@ -0,0 +1,117 @@
+use components::*;
+use piston_window::*;
+use vecmath::*;
+use collisions::*;
+use arena::*;
+use camera::*;
+use common::*;
+use super::ui::*;
+
+const BACKGROUND: [f32; 4] = [0.1, 0.1, 0.1, 0.8];
+const EDGE_COLOR: [f32; 4] = [0.6, 0.6, 0.6, 1.0];
+const VIEW_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+/// on-map size of each kind of blip, in pixels
+const SHIP_BLIP: f64 = 4.0;
+const PROJECTILE_BLIP: f64 = 2.0;
+const DEBRIS_BLIP: f64 = 1.0;
+
+/// The whole arena shrunk into a panel: every ship and projectile,
+/// the part the camera is showing, and click (or drag) to look somewhere else
+pub struct Minimap {
+    camera: Shared<Camera>,
+    arena: Option<Arena>,
+    blips: Vec<Blip>,
+    w: f64,
+    h: f64,
+}
+
+impl Minimap {
+    /// Panel pixels per arena unit, keeping the arena's proportions
+    fn scale(&self) -> f64 {
+        match self.arena {
+            Some(a) => f64::min(self.w / a.w, self.h / a.h),
+            None => 0.0,
+        }
+    }
+    fn to_map(&self, p: Vector2<f64>) -> Vector2<f64> {
+        vec2_scale(p, self.scale())
+    }
+    fn jump(&mut self, x: f64, y: f64) {
+        let arena = match self.arena {
+            Some(a) => a,
+            None => return,
+        };
+        let s = self.scale();
+        let p = [
+            (x / s).max(0.0).min(arena.w),
+            (y / s).max(0.0).min(arena.h),
+        ];
+        update(&self.camera, |cam| {
+            cam.centre = p;
+            cam.follow = None;
+        });
+    }
+}
+
+impl Component for Minimap {
+    fn bounding_box(&self) -> BoundingBox {
+        [0.0, 0.0, self.w, self.h]
+    }
+    fn tick(&mut self, _: &UpdateArgs, w: &mut World) {
+        self.arena = Some(w.arena());
+        self.blips = w.others().filter_map(|c| c.blip()).collect();
+    }
+    fn click(&mut self, x: f64, y: f64, _: &mut World) {
+        self.jump(x, y);
+    }
+    fn drag(&mut self, x: f64, y: f64, _: &mut World) {
+        self.jump(x, y);
+    }
+    fn draw(&mut self, ctx: Context, g: &mut G2d) {
+        let arena = match self.arena {
+            Some(a) => a,
+            None => return,
+        };
+        let size = self.to_map(arena.size());
+        let field = [0.0, 0.0, size[0], size[1]];
+        Rectangle::new(BACKGROUND).draw(field, &ctx.draw_state, ctx.transform, g);
+        Rectangle::new_border(EDGE_COLOR, 1.0).draw(field, &ctx.draw_state, ctx.transform, g);
+
+        for b in self.blips.iter() {
+            let size = match b.kind {
+                BlipKind::Ship => SHIP_BLIP,
+                BlipKind::Projectile => PROJECTILE_BLIP,
+                BlipKind::Debris => DEBRIS_BLIP,
+            };
+            rectangle(b.color, bb_around(self.to_map(b.pos), size, size), ctx.transform, g);
+        }
+
+        let visible = self.camera.get().visible();
+        let tl = self.to_map([visible[0], visible[1]]);
+        let wh = self.to_map([visible[2], visible[3]]);
+        Rectangle::new_border(VIEW_COLOR, 0.5).draw(
+            [tl[0], tl[1], wh[0], wh[1]],
+            &ctx.draw_state,
+            ctx.transform,
+            g,
+        );
+    }
+}
+
+pub fn make_minimap<T: GameObjectFactory>(
+    fact: &mut T,
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+    camera: Shared<Camera>,
+) -> GameObject {
+    let minimap = Minimap {
+        camera: camera,
+        arena: None,
+        blips: Vec::new(),
+        w: w,
+        h: h,
+    };
+    fact.new_gameobject().add(Box::new(UI::new(minimap, x, y)))
+}
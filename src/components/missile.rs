@@ -0,0 +1,102 @@
+use piston_window::*;
+use vecmath::*;
+use components::*;
+use collisions::*;
+use arc::*;
+use super::weapon::*;
+use team::*;
+
+const MISSILE_SPEED: f64 = 1.2;
+/// radians per tick
+const MISSILE_TURN: f64 = 0.04;
+const MISSILE_DAMAGE: u32 = 2;
+const MISSILE_SIZE: f64 = 4.0;
+
+/// A projectile that steers towards its target each tick.
+/// If the target is gone it flies straight on.
+pub struct Missile {
+    shot: Shot,
+    pos: Vector2<f64>,
+    heading: f64, // radians, atan2 convention
+    target: Option<UUID>,
+    range: f64,
+}
+
+impl Missile {
+    pub fn new(shot: Shot, target: Option<UUID>) -> Missile {
+        Missile {
+            shot: shot,
+            pos: shot.pos,
+            heading: shot.dir[1].atan2(shot.dir[0]),
+            target: target,
+            range: shot.range,
+        }
+    }
+    fn steer(&mut self, w: &World) {
+        let goal = self.target
+            .and_then(|id| w.find(id))
+            .and_then(|c| c.collider())
+            .map(bb_center);
+        if let Some(goal) = goal {
+            let v = vec2_sub(goal, self.pos);
+            let d = angle_diff(self.heading, v[1].atan2(v[0]));
+            self.heading += d.max(-MISSILE_TURN).min(MISSILE_TURN);
+        }
+    }
+}
+
+impl Component for Missile {
+    fn tick(&mut self, args: &UpdateArgs, w: &mut World) {
+        self.steer(w);
+        let vel = vec2_scale([self.heading.cos(), self.heading.sin()], MISSILE_SPEED);
+        self.range -= MISSILE_SPEED;
+        match w.arena().confine_projectile(vec2_add(self.pos, vel), vel) {
+            Some((pos, vel)) => {
+                self.pos = pos;
+                self.heading = vel[1].atan2(vel[0]);
+            }
+            None => {
+                w.destroy_self();
+                return;
+            }
+        }
+        let area = bb_around(self.pos, MISSILE_SIZE, MISSILE_SIZE);
+        let hit = hits(w, area, self.shot.owner, self.shot.player);
+        if let Some(&id) = hit.first() {
+            w.damage(id, MISSILE_DAMAGE);
+            w.destroy_self();
+        } else if self.range <= 0.0 {
+            w.destroy_self();
+        }
+    }
+    fn hit(&mut self, _: u32, w: &mut World) {
+        w.destroy_self();
+    }
+    fn draw(&mut self, c: Context, g: &mut G2d) {
+        Rectangle::new(self.shot.color).draw(
+            [-MISSILE_SIZE / 2.0, -MISSILE_SIZE / 4.0, MISSILE_SIZE, MISSILE_SIZE / 2.0],
+            &c.draw_state,
+            c.transform.trans(self.pos[0], self.pos[1]).rot_rad(self.heading),
+            g,
+        );
+    }
+    fn collider(&self) -> Option<BoundingBox> {
+        Some(bb_around(self.pos, MISSILE_SIZE, MISSILE_SIZE))
+    }
+    fn owner(&self) -> Option<UUID> {
+        self.shot.owner
+    }
+    fn player(&self) -> Option<PlayerId> {
+        self.shot.player
+    }
+    fn blip(&self) -> Option<Blip> {
+        Some(Blip {
+            kind: BlipKind::Projectile,
+            pos: self.pos,
+            color: self.shot.color,
+        })
+    }
+    fn interceptable(&self) -> bool {
+        true
+    }
+}
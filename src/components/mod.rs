@@ -1,25 +1,86 @@
 pub mod bullet;
+pub mod beam;
+pub mod debris;
+pub mod flak;
+pub mod fleet;
+pub mod minimap;
+pub mod missile;
+pub mod point_defense;
+pub mod rebind;
+pub mod seat;
+pub mod weapon;
 pub mod ship;
+pub mod throttle;
 pub mod ui;
+pub mod viewport;
+pub mod widget;
 
 use piston_window::G2d;
 use piston::input::*;
 use piston_window::Context;
+use std::collections::linked_list;
 use std::collections::linked_list::*;
 use std::collections::hash_set::*;
+use std::collections::HashMap;
 use std::iter::*;
 use std::any::Any;
+use vecmath::*;
 
 use collisions::*;
+use events::*;
+use rng::*;
+use physics::*;
+use arena::*;
+use team::*;
+use bindings::*;
 
 pub type UUID = u32;
 
+const EVENT_LOG_SIZE: usize = 100;
+
+/// Everything the World needs to carry from one pass over the
+/// GameObjects to the next
+pub struct WorldState {
+    pub id_counter: UUID,
+    /// damage waiting to be handed to a GameObject the next time it runs
+    pub damage: HashMap<UUID, u32>,
+    pub events: EventLog,
+    pub rng: Rng,
+    pub movement: Movement,
+    pub arena: Arena,
+    pub roster: Roster,
+    /// buttons down right now
+    pub held: Vec<Button>,
+    /// what each seat's held buttons are bound to
+    pub holding: Vec<(usize, Action)>,
+    /// some seat's bindings have changed and want saving
+    pub rebound: bool,
+}
+
+impl WorldState {
+    pub fn new() -> WorldState {
+        WorldState {
+            id_counter: 0,
+            damage: HashMap::new(),
+            events: EventLog::new(EVENT_LOG_SIZE),
+            rng: Rng::new(0),
+            movement: Movement::RealTime,
+            arena: Arena::new(700.0, 500.0, Boundary::Clamp),
+            roster: Roster::new(Rules { friendly_fire: false }),
+            held: Vec::new(),
+            holding: Vec::new(),
+            rebound: false,
+        }
+    }
+}
+
 pub struct World {
     prev: LinkedList<GameObject>,
     rest: LinkedList<GameObject>,
     spawned: LinkedList<GameObject>,
     to_destroy: HashSet<UUID>,
-    id_counter: UUID,
+    state: WorldState,
+    current: Option<UUID>,
 }
 
 pub trait GameObjectFactory {
@@ -27,8 +88,8 @@ pub trait GameObjectFactory {
 }
 impl GameObjectFactory for World {
     fn new_gameobject(&mut self) -> GameObject {
-        let id = self.id_counter;
-        self.id_counter += 1;
+        let id = self.state.id_counter;
+        self.state.id_counter += 1;
         GameObject {
             components: LinkedList::new(),
             id,
@@ -39,7 +100,7 @@ impl GameObjectFactory for World {
 impl World {
     pub fn new(
         mut components: LinkedList<GameObject>,
-        id_counter: UUID,
+        state: WorldState,
     ) -> (World, Option<GameObject>) {
         let r = components.pop_back();
         let w = World {
@@ -47,32 +108,42 @@ impl World {
             rest: LinkedList::new(),
             spawned: LinkedList::new(),
             to_destroy: HashSet::new(),
-            id_counter: id_counter,
+            state: state,
+            current: r.as_ref().map(|c| c.id),
         };
         (w, r)
     }
 
     pub fn rotate(&mut self, c: GameObject) -> Option<GameObject> {
         self.rest.push_front(c);
-        self.prev.pop_back()
+        let r = self.prev.pop_back();
+        self.current = r.as_ref().map(|c| c.id);
+        r
     }
-    pub fn complete(self) -> (LinkedList<GameObject>, UUID) {
+    pub fn complete(self) -> (LinkedList<GameObject>, WorldState) {
         match self {
             World {
                 prev: _,
                 rest,
                 spawned,
                 to_destroy: destroy,
-                id_counter,
+                mut state,
+                current: _,
             } => {
                 let obj = rest.into_iter()
                     .filter(|c| !destroy.contains(&c.id))
                     .chain(spawned.into_iter())
                     .collect::<LinkedList<_>>();
-                (obj, id_counter)
+                let alive = obj.iter().map(|c| c.id).collect::<HashSet<_>>();
+                state.damage.retain(|id, _| alive.contains(id));
+                (obj, state)
             }
         }
     }
+    /// Remove and return the damage waiting for the given GameObject
+    pub fn take_damage(&mut self, id: UUID) -> u32 {
+        self.state.damage.remove(&id).unwrap_or(0)
+    }
 }
 
 impl World {
@@ -86,13 +157,137 @@ impl World {
     pub fn destroy(&mut self, comp: &GameObject) {
         self.to_destroy.insert(comp.id);
     }
+    /// The id of the GameObject whose components are currently being run
+    pub fn current(&self) -> Option<UUID> {
+        self.current
+    }
+    /// Destroy the GameObject whose components are currently being run.
+    /// Lets a component remove its owner without a reference to it.
+    pub fn destroy_self(&mut self) {
+        if let Some(id) = self.current {
+            self.to_destroy.insert(id);
+        }
+    }
+    /// Every GameObject other than the one currently running
+    pub fn others<'a>(
+        &'a self,
+    ) -> Chain<linked_list::Iter<'a, GameObject>, linked_list::Iter<'a, GameObject>> {
+        self.prev.iter().chain(self.rest.iter())
+    }
+    pub fn find(&self, id: UUID) -> Option<&GameObject> {
+        self.others().find(|c| c.id == id)
+    }
     pub fn collisions(&self, bb: BoundingBox) -> LinkedList<&GameObject> {
-        self.prev
-            .iter()
-            .chain(self.rest.iter())
-            .filter(|c| collides_box(c.bounding_box(), bb))
+        self.others()
+            .filter(|c| c.collider().map_or(false, |b| overlaps(b, bb)))
             .collect::<LinkedList<_>>()
     }
+    /// Deal damage to a GameObject. It is delivered through `Component::hit`
+    /// the next time that object runs.
+    pub fn damage(&mut self, id: UUID, amount: u32) {
+        *self.state.damage.entry(id).or_insert(0) += amount;
+    }
+    pub fn movement(&self) -> Movement {
+        self.state.movement
+    }
+    pub fn arena(&self) -> Arena {
+        self.state.arena
+    }
+    pub fn roster(&self) -> &Roster {
+        &self.state.roster
+    }
+    /// Ask for the bindings to be saved, once they've been changed
+    pub fn rebound(&mut self) {
+        self.state.rebound = true;
+    }
+    pub fn is_held(&self, b: &Button) -> bool {
+        self.state.held.contains(b)
+    }
+    /// Is `seat` holding down a button bound to `action`?
+    pub fn holding(&self, seat: usize, action: Action) -> bool {
+        self.state.holding.contains(&(seat, action))
+    }
+    /// Record something worth telling the players about
+    pub fn log(&mut self, event: String) {
+        self.state.events.push(event);
+    }
+    /// True with probability `p`
+    pub fn roll(&mut self, p: f64) -> bool {
+        self.state.rng.chance(p)
+    }
+    /// Uniform in [0, 1)
+    pub fn random(&mut self) -> f64 {
+        self.state.rng.next_f64()
+    }
+    /// First collider along the ray, and how far along `dir` it is.
+    /// Anything belonging to `ignore`, or that `player` isn't allowed to hurt,
+    /// is passed through.
+    pub fn raycast(
+        &self,
+        origin: Vector2<f64>,
+        dir: Vector2<f64>,
+        max: f64,
+        ignore: Option<UUID>,
+        player: Option<PlayerId>,
+    ) -> Option<(UUID, f64)> {
+        let mut best = None;
+        for c in self.others() {
+            if ignore.is_some() && (Some(c.id) == ignore || c.owner() == ignore) {
+                continue;
+            }
+            if !self.state.roster.may_damage(player, c.player()) {
+                continue;
+            }
+            if let Some(t) = c.collider().and_then(|b| ray_box(origin, dir, b)) {
+                if t <= max && best.map_or(true, |(_, b)| t < b) {
+                    best = Some((c.id, t));
+                }
+            }
+        }
+        best
+    }
+    /// The closest collidable (i.e. solid, like a ship) GameObject to `p`
+    /// that isn't an ally of `player`
+    pub fn nearest(
+        &self,
+        p: Vector2<f64>,
+        ignore: Option<UUID>,
+        player: Option<PlayerId>,
+    ) -> Option<(UUID, Vector2<f64>)> {
+        let mut best: Option<(UUID, Vector2<f64>, f64)> = None;
+        for c in self.others() {
+            if Some(c.id) == ignore || !c.collidable() {
+                continue;
+            }
+            if self.state.roster.allied(player, c.player()) {
+                continue;
+            }
+            if let Some(b) = c.collider() {
+                let center = bb_center(b);
+                let d = vec2_square_len(vec2_sub(center, p));
+                if best.map_or(true, |(_, _, bd)| d < bd) {
+                    best = Some((c.id, center, d));
+                }
+            }
+        }
+        best.map(|(id, center, _)| (id, center))
+    }
+}
+
+/// What kind of mark something leaves on the minimap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlipKind {
+    Ship,
+    Projectile,
+    Debris,
+}
+
+/// How something shows up on the minimap
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Blip {
+    pub kind: BlipKind,
+    pub pos: Vector2<f64>,
+    pub color: [f32; 4],
 }
 
 pub trait Component: 'static {
@@ -101,8 +296,32 @@ pub trait Component: 'static {
     fn press(&mut self, &Button, &mut World) {}
     fn release(&mut self, &Button, &mut World) {}
     fn click(&mut self, x: f64, y: f64, &mut World) {}
+    /// The left button went down at (x, y). `UI` and `Viewport` keep sending
+    /// `drag` and `drag_end` to whatever this landed on, wherever the pointer goes.
+    fn drag_start(&mut self, x: f64, y: f64, &mut World) {}
     fn drag(&mut self, x: f64, y: f64, &mut World) {}
+    fn drag_end(&mut self, x: f64, y: f64, &mut World) {}
+    /// The pointer moved, button down or not
+    fn pointer_move(&mut self, x: f64, y: f64, &mut World) {}
+    fn hover_enter(&mut self, x: f64, y: f64, &mut World) {}
+    fn hover_leave(&mut self, &mut World) {}
+    /// The wheel turned `dy` notches, away from the player being positive
+    fn scroll(&mut self, x: f64, y: f64, dy: f64, &mut World) {}
+    /// A right click that wasn't a pan
+    fn context_click(&mut self, x: f64, y: f64, &mut World) {}
+    /// Keyboard focus arrived or left
+    fn set_focus(&mut self, focused: bool) {}
+    /// A key press for whatever has keyboard focus. True if it was used, in
+    /// which case it isn't also treated as an action.
+    fn focus_key(&mut self, &Button, &mut World) -> bool {
+        false
+    }
     fn destroy(&mut self, &mut World) {}
+    fn hit(&mut self, damage: u32, &mut World) {}
+    /// The turn is over and everyone's orders play out (turn-based movement only)
+    fn end_turn(&mut self, &mut World) {}
+    /// The player at seat `seat` asked for `action`
+    fn action(&mut self, seat: usize, action: Action, &mut World) {}
 
     fn bounding_box(&self) -> BoundingBox {
         [0.0; 4]
@@ -111,6 +330,31 @@ pub trait Component: 'static {
     fn collidable(&self) -> bool {
         false
     }
+
+    /// Where this is in the arena, for things that can be hit
+    fn collider(&self) -> Option<BoundingBox> {
+        None
+    }
+
+    /// The GameObject that fired this, for projectiles
+    fn owner(&self) -> Option<UUID> {
+        None
+    }
+
+    /// Can point defense shoot this down?
+    fn interceptable(&self) -> bool {
+        false
+    }
+
+    /// How this shows on the minimap, for things in the arena
+    fn blip(&self) -> Option<Blip> {
+        None
+    }
+
+    /// The player this belongs to, if anyone
+    fn player(&self) -> Option<PlayerId> {
+        None
+    }
 }
 
 // These just don't work unless all components are
@@ -159,11 +403,54 @@ impl Component for GameObject {
             comp.release(b, w);
         }
     }
+    fn drag_start(&mut self, x: f64, y: f64, w: &mut World) {
+        for comp in self.components.iter_mut() {
+            comp.drag_start(x, y, w);
+        }
+    }
     fn drag(&mut self, x: f64, y: f64, w: &mut World) {
         for comp in self.components.iter_mut() {
             comp.drag(x, y, w);
         }
     }
+    fn drag_end(&mut self, x: f64, y: f64, w: &mut World) {
+        for comp in self.components.iter_mut() {
+            comp.drag_end(x, y, w);
+        }
+    }
+    fn pointer_move(&mut self, x: f64, y: f64, w: &mut World) {
+        for comp in self.components.iter_mut() {
+            comp.pointer_move(x, y, w);
+        }
+    }
+    fn hover_enter(&mut self, x: f64, y: f64, w: &mut World) {
+        for comp in self.components.iter_mut() {
+            comp.hover_enter(x, y, w);
+        }
+    }
+    fn hover_leave(&mut self, w: &mut World) {
+        for comp in self.components.iter_mut() {
+            comp.hover_leave(w);
+        }
+    }
+    fn scroll(&mut self, x: f64, y: f64, dy: f64, w: &mut World) {
+        for comp in self.components.iter_mut() {
+            comp.scroll(x, y, dy, w);
+        }
+    }
+    fn context_click(&mut self, x: f64, y: f64, w: &mut World) {
+        for comp in self.components.iter_mut() {
+            comp.context_click(x, y, w);
+        }
+    }
+    fn set_focus(&mut self, focused: bool) {
+        for comp in self.components.iter_mut() {
+            comp.set_focus(focused);
+        }
+    }
+    fn focus_key(&mut self, b: &Button, w: &mut World) -> bool {
+        self.components.iter_mut().any(|comp| comp.focus_key(b, w))
+    }
     fn click(&mut self, x: f64, y: f64, w: &mut World) {
         for comp in self.components.iter_mut() {
             comp.click(x, y, w);
@@ -174,6 +461,21 @@ impl Component for GameObject {
             comp.destroy(w);
         }
     }
+    fn hit(&mut self, damage: u32, w: &mut World) {
+        for comp in self.components.iter_mut() {
+            comp.hit(damage, w);
+        }
+    }
+    fn end_turn(&mut self, w: &mut World) {
+        for comp in self.components.iter_mut() {
+            comp.end_turn(w);
+        }
+    }
+    fn action(&mut self, seat: usize, action: Action, w: &mut World) {
+        for comp in self.components.iter_mut() {
+            comp.action(seat, action, w);
+        }
+    }
 
     fn bounding_box(&self) -> BoundingBox {
         let mut bb = [0.0; 4];
@@ -195,4 +497,24 @@ impl Component for GameObject {
         }
         return false;
     }
+
+    fn collider(&self) -> Option<BoundingBox> {
+        self.components.iter().filter_map(|c| c.collider()).next()
+    }
+
+    fn owner(&self) -> Option<UUID> {
+        self.components.iter().filter_map(|c| c.owner()).next()
+    }
+
+    fn interceptable(&self) -> bool {
+        self.components.iter().any(|c| c.interceptable())
+    }
+
+    fn blip(&self) -> Option<Blip> {
+        self.components.iter().filter_map(|c| c.blip()).next()
+    }
+
+    fn player(&self) -> Option<PlayerId> {
+        self.components.iter().filter_map(|c| c.player()).next()
+    }
 }
\ No newline at end of file
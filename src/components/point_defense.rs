@@ -0,0 +1,111 @@
+use piston_window::*;
+use vecmath::*;
+use components::*;
+use collisions::*;
+use common::*;
+use arc::*;
+use math::*;
+use super::ship::Pose;
+use super::weapon::*;
+use team::*;
+
+/// Static description of a point defense mount
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PointDefenseSpec {
+    /// relative to the hull, same convention as the main guns
+    pub arc: FiringArc,
+    pub range: f64,
+    /// seconds between shots
+    pub reload: f64,
+    /// probability that a shot destroys its target
+    pub hit_chance: f64,
+}
+
+/// seconds a tracer stays on screen
+const TRACER_TIME: f64 = 0.1;
+
+/// Automatically shoots at the closest hostile interceptable
+/// projectile inside its arc and range
+pub struct PointDefense {
+    spec: PointDefenseSpec,
+    pose: Shared<Pose>,
+    player: Option<PlayerId>,
+    color: [f32; 4],
+    reload_left: f64,
+    tracer: Option<(Vector2<f64>, f64)>,
+}
+
+impl PointDefense {
+    pub fn new(
+        spec: PointDefenseSpec,
+        pose: Shared<Pose>,
+        player: Option<PlayerId>,
+        color: [f32; 4],
+    ) -> PointDefense {
+        PointDefense {
+            spec: spec,
+            pose: pose,
+            player: player,
+            color: color,
+            reload_left: 0.0,
+            tracer: None,
+        }
+    }
+    fn in_arc(&self, pose: Pose, p: Vector2<f64>) -> bool {
+        let v = vec2_sub(p, pose.pos);
+        // the angle a gun would need to point along v, see Ship::fire_gun
+        let a = v[0].atan2(-v[1]) - vec2_angle(pose.dir);
+        vec2_len(v) <= self.spec.range && self.spec.arc.contains(a)
+    }
+    fn target(&self, w: &World) -> Option<(UUID, Vector2<f64>)> {
+        let pose = self.pose.get();
+        let me = w.current();
+        let area = bb_around(pose.pos, self.spec.range * 2.0, self.spec.range * 2.0);
+        let mut best: Option<(UUID, Vector2<f64>, f64)> = None;
+        for c in w.collisions(area) {
+            if !c.interceptable() || owned_by(c, me) || w.roster().allied(self.player, c.player()) {
+                continue;
+            }
+            if let Some(p) = c.collider().map(bb_center) {
+                let d = vec2_square_len(vec2_sub(p, pose.pos));
+                if self.in_arc(pose, p) && best.map_or(true, |(_, _, bd)| d < bd) {
+                    best = Some((c.id, p, d));
+                }
+            }
+        }
+        best.map(|(id, p, _)| (id, p))
+    }
+}
+
+impl Component for PointDefense {
+    fn tick(&mut self, args: &UpdateArgs, w: &mut World) {
+        self.tracer = match self.tracer {
+            Some((p, t)) if t > args.dt => Some((p, t - args.dt)),
+            _ => None,
+        };
+        self.reload_left = f64::max(self.reload_left - args.dt, 0.0);
+        if self.reload_left > 0.0 || !self.pose.get().alive {
+            return;
+        }
+        if let Some((id, p)) = self.target(w) {
+            self.reload_left = self.spec.reload;
+            self.tracer = Some((p, TRACER_TIME));
+            if w.roll(self.spec.hit_chance) {
+                w.damage(id, 1);
+                let me = w.current().unwrap_or(0);
+                w.log(format!("ship {} point defense intercepted {}", me, id));
+            }
+        }
+    }
+    fn draw(&mut self, c: Context, g: &mut G2d) {
+        if let Some((p, _)) = self.tracer {
+            let from = self.pose.get().pos;
+            Line::new(self.color, 0.3).draw(
+                [from[0], from[1], p[0], p[1]],
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use components::*;
+use piston_window::*;
+use common::*;
+use bindings::*;
+use super::seat::*;
+use super::ui::*;
+use super::widget::write;
+use font::HEIGHT;
+
+const ROW: f64 = 20.0;
+/// width of the swatch naming each action; click it to add a binding
+const SWATCH: f64 = 80.0;
+const BACKGROUND: [f32; 4] = [0.9, 0.9, 0.9, 0.95];
+const LISTENING_COLOR: [f32; 4] = [1.0, 0.8, 0.0, 1.0];
+const TEXT_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+/// A colour for each action, so rows can be told apart at a glance
+fn swatch(i: usize) -> [f32; 4] {
+    let t = i as f32 / ACTIONS.len() as f32;
+    [t, 1.0 - t, (t * 3.0) % 1.0, 1.0]
+}
+
+/// The seat's bindings, one row per action listing its buttons.
+/// Opened and closed with the Rebind action. Clicking an action's name
+/// binds the next key pressed to it, and clicking it again cancels that.
+/// Clicking its buttons clears them.
+pub struct Rebinder {
+    seat: usize,
+    bindings: SharedRef<Bindings>,
+    open: bool,
+    w: f64,
+}
+
+impl Component for Rebinder {
+    fn bounding_box(&self) -> BoundingBox {
+        [0.0, 0.0, self.w, ROW * ACTIONS.len() as f64]
+    }
+    fn action(&mut self, seat: usize, action: Action, _: &mut World) {
+        if seat == self.seat && action == Action::Rebind {
+            self.open = !self.open;
+            self.bindings.borrow_mut().listen(None);
+        }
+    }
+    fn click(&mut self, x: f64, y: f64, w: &mut World) {
+        if !self.open {
+            return;
+        }
+        let a = match ACTIONS.get((y / ROW) as usize) {
+            Some(&a) => a,
+            None => return,
+        };
+        let mut bindings = self.bindings.borrow_mut();
+        if x < SWATCH {
+            // Escape would close the window, so the swatch is how to back out
+            let listen = if bindings.listening() == Some(a) { None } else { Some(a) };
+            bindings.listen(listen);
+        } else {
+            bindings.clear(a);
+            w.rebound();
+        }
+    }
+    fn draw(&mut self, ctx: Context, g: &mut G2d) {
+        if !self.open {
+            return;
+        }
+        let bounds = self.bounding_box();
+        Rectangle::new(BACKGROUND).draw(bounds, &ctx.draw_state, ctx.transform, g);
+        let bindings = self.bindings.borrow();
+        for (i, &a) in ACTIONS.iter().enumerate() {
+            let y = i as f64 * ROW;
+            let row = [0.0, y + 2.0, SWATCH, ROW - 4.0];
+            Rectangle::new(swatch(i)).draw(row, &ctx.draw_state, ctx.transform, g);
+            if bindings.listening() == Some(a) {
+                Rectangle::new_border(LISTENING_COLOR, 2.0).draw(
+                    [0.0, y, self.w, ROW],
+                    &ctx.draw_state,
+                    ctx.transform,
+                    g,
+                );
+            }
+            let text_y = y + (ROW - HEIGHT) / 2.0;
+            write(a.name(), 4.0, text_y, TEXT_COLOR, ctx, g);
+            let buttons = if bindings.listening() == Some(a) {
+                "press a key, click to cancel".to_string()
+            } else {
+                let names: Vec<_> = bindings
+                    .buttons(a)
+                    .iter()
+                    .map(|b| button_name(b).unwrap_or("?"))
+                    .collect();
+                names.join(", ")
+            };
+            write(&buttons, SWATCH + 4.0, text_y, TEXT_COLOR, ctx, g);
+        }
+    }
+}
+
+pub fn make_rebinder<T: GameObjectFactory>(
+    fact: &mut T,
+    x: f64,
+    y: f64,
+    seat: &Seat,
+) -> GameObject {
+    let rebinder = Rebinder {
+        seat: seat.id,
+        bindings: seat.bindings.clone(),
+        open: false,
+        w: 260.0,
+    };
+    fact.new_gameobject().add(Box::new(UI::new(rebinder, x, y)))
+}
@@ -0,0 +1,18 @@
+use camera::*;
+use common::*;
+use bindings::*;
+use super::fleet::*;
+
+/// One place at the game: a view of the arena, the ships it gives orders
+/// to and the buttons it uses. Split-screen has a seat per player; hot-seat
+/// has one seat that the players take turns in.
+#[derive(Clone)]
+pub struct Seat {
+    /// actions are delivered tagged with this, so components know whose they are
+    pub id: usize,
+    /// the seat's section in the bindings config file
+    pub name: &'static str,
+    pub camera: Shared<Camera>,
+    pub fleet: SharedRef<Fleet>,
+    pub bindings: SharedRef<Bindings>,
+}
@@ -2,229 +2,761 @@ use components::*;
 use vecmath::*;
 use piston_window::*;
 use super::bullet::*;
+use super::weapon::*;
+use super::point_defense::*;
+use super::debris::*;
+use super::throttle::*;
 use super::ui::*;
+use super::viewport::*;
+use super::fleet::*;
+use super::seat::*;
+use super::widget::*;
+use bindings::*;
+use camera::*;
 use math::*;
 use graphics::math::*;
-use piston_window::Button::*;
 use common::*;
+use collisions::*;
+use arc::*;
+use team::*;
+use layout::*;
+use turret::*;
+use physics::*;
+use std::f64::consts::PI;
+use std::collections::HashSet;
+
+/// The stats that differ between kinds of ship
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShipClass {
+    pub engine: Engine,
+    pub front_gun: GunSpec,
+    pub back_gun: GunSpec,
+    pub point_defense: Option<PointDefenseSpec>,
+    /// pieces thrown off when the ship is destroyed
+    pub debris: u32,
+}
+
+/// Where a ship is and which way it faces, for the parts of it
+/// that live in other components
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pose {
+    pub pos: Vector2<f64>,
+    pub dir: Vector2<f64>,
+    pub alive: bool,
+    /// units per second
+    pub speed: f64,
+    pub health: u32,
+}
+
+pub const CAPITAL: ShipClass = ShipClass {
+    engine: Engine {
+        thrust: 10.0,
+        max_speed: 30.0,
+        drag: 0.0,
+        turn_rate: 0.6,
+        turn_accel: Some(0.8),
+    },
+    front_gun: GunSpec {
+        weapon: WeaponKind::Cannon,
+        reload: 0.5,
+        ammo: None,
+        heat_per_shot: 1.0,
+        cooling: 0.75,
+        max_heat: 5.0,
+    },
+    back_gun: GunSpec {
+        weapon: WeaponKind::Missile,
+        reload: 2.0,
+        ammo: Some(8),
+        heat_per_shot: 1.0,
+        cooling: 0.5,
+        max_heat: 4.0,
+    },
+    point_defense: Some(PointDefenseSpec {
+        arc: FiringArc {
+            centre: 0.0,
+            half_width: PI,
+        },
+        range: 60.0,
+        reload: 0.3,
+        hit_chance: 0.5,
+    }),
+    debris: 4,
+};
+
+pub const FRIGATE: ShipClass = ShipClass {
+    engine: Engine {
+        thrust: 20.0,
+        max_speed: 45.0,
+        drag: 0.1,
+        turn_rate: 1.5,
+        turn_accel: None,
+    },
+    front_gun: GunSpec {
+        weapon: WeaponKind::Laser,
+        reload: 0.25,
+        ammo: None,
+        heat_per_shot: 2.0,
+        cooling: 1.5,
+        max_heat: 8.0,
+    },
+    back_gun: GunSpec {
+        weapon: WeaponKind::Flak,
+        reload: 0.75,
+        ammo: Some(30),
+        heat_per_shot: 1.0,
+        cooling: 0.5,
+        max_heat: 5.0,
+    },
+    point_defense: None,
+    debris: 2,
+};
+
+const HULL_HEALTH: u32 = 3;
+const RAM_DAMAGE: u32 = 1;
+const HULK_COLOR: [f32; 4] = [0.4, 0.4, 0.4, 1.0];
+/// how hard debris is thrown clear of the hulk, units per second
+const DEBRIS_SCATTER: f64 = 30.0;
 
 pub struct Ship {
+    player: PlayerId,
+    /// the owning player's team colour
     color: [f32; 4],
     pos: Vector2<f64>,
+    /// heading the helm has ordered, radians
     orient: Shared<f64>,
+    /// heading the hull actually points, radians
+    heading: f64,
+    /// radians per second
+    spin: f64,
+    /// unit vector the bow points along, always (cos heading, sin heading)
     dir: Vector2<f64>,
+    /// units per second, independent of `dir`
+    vel: Vector2<f64>,
+    /// throttle, set by the Throttle widget, MAX_REVERSE to MAX_THROTTLE
     speed: Shared<f64>,
+    engine: Engine,
     w: f64,
     h: f64,
     orientation_front_gun: Shared<f64>,
     front_gun: f64,
     orientation_back_gun: Shared<f64>,
     back_gun: f64,
-    sw: f64,
-    sh: f64,
+    front_arc: FiringArc,
+    back_arc: FiringArc,
+    front_state: Shared<GunState>,
+    back_state: Shared<GunState>,
+    health: u32,
+    debris: u32,
+    pose: Shared<Pose>,
+    /// ships we're touching, so a ram only does damage once
+    contacts: HashSet<UUID>,
+    /// guns told to fire in turn-based play, which they do when the turn resolves
+    fire_orders: Vec<Mount>,
+    /// the camera of the seat giving this ship orders, for following it
+    camera: Shared<Camera>,
+    /// every camera, for whatever the ship spawns
+    cameras: Cameras,
+    /// the seat whose actions this ship answers to
+    seat: usize,
+    id: UUID,
+    fleet: SharedRef<Fleet>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mount {
+    Front,
+    Back,
+}
+
+const GUN_DIAL: f64 = 100.0;
+const GUN_COLOR: [f32; 4] = [0.0, 1.0, 0.0, 1.0];
+const HELM_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+/// the heading dial sticks to multiples of this
+const HELM_SNAP: f64 = PI / 36.0;
+/// width of the column of buttons beside the throttle
+const SIDE_WIDTH: f64 = 70.0;
+
+/// The orientation and state of the selected ship's gun on `mount`
+fn gun_controls(fleet: &Fleet, mount: Mount) -> Option<(Shared<f64>, Shared<GunState>)> {
+    fleet.selected().map(|s| match mount {
+        Mount::Front => (s.front_gun, s.front_state),
+        Mount::Back => (s.back_gun, s.back_state),
+    })
+}
+
+/// Dial aiming one of the selected ship's guns, with its reload, heat and ammo
 struct Gun {
-    dir: Shared<f64>, // radians
-    size: f64,
+    dial: Dial,
+    mount: Mount,
+    fleet: SharedRef<Fleet>,
 }
 
 impl Component for Gun {
     fn bounding_box(&self) -> BoundingBox {
-        [0.0, 0.0, self.size, self.size]
+        self.dial.bounding_box()
     }
-    fn drag(&mut self, x: f64, y: f64, _: &mut World) {
-        let v = vec2_sub(
-            [self.size, self.size],
-            [x + (self.size / 2.0), y + (self.size / 2.0)],
-        );
-        let a = vec2_angle(v);
-        if !a.is_nan() {
-            self.dir.set(a);
-        }
+    fn drag_start(&mut self, x: f64, y: f64, w: &mut World) {
+        self.dial.drag_start(x, y, w)
+    }
+    fn drag(&mut self, x: f64, y: f64, w: &mut World) {
+        self.dial.drag(x, y, w)
+    }
+    fn set_focus(&mut self, focused: bool) {
+        self.dial.set_focus(focused)
+    }
+    fn focus_key(&mut self, b: &Button, w: &mut World) -> bool {
+        self.dial.focus_key(b, w)
     }
     fn draw(&mut self, ctx: Context, g: &mut G2d) {
-        let bounds = [0.0, 0.0, self.size, self.size];
-        Rectangle::new([1.0; 4]).draw(bounds, &ctx.draw_state, ctx.transform, g);
-        Rectangle::new_border([0.0, 0.0, 0.0, 1.0], 1.0).draw(
-            bounds,
+        let state = gun_controls(&self.fleet.borrow(), self.mount).map(|(_, s)| s.get());
+        let border = match state {
+            Some(ref s) if s.overheated => [1.0, 0.0, 0.0, 1.0],
+            _ => [0.0, 0.0, 0.0, 1.0],
+        };
+        self.dial.draw_dial(border, ctx, g);
+        let state = match state {
+            Some(s) => s,
+            None => return,
+        };
+        let size = GUN_DIAL;
+
+        // reload along the top, heat along the bottom
+        let bar = size * 0.05;
+        Rectangle::new([0.5, 0.5, 0.5, 1.0]).draw(
+            [0.0, 0.0, size * state.reload_fraction(), bar],
             &ctx.draw_state,
             ctx.transform,
             g,
         );
-
-        Rectangle::new([0.0, 1.0, 0.0, 1.0]).draw(
-            [0.0, 0.0, self.size * 0.8 * 0.5, self.size * 0.05],
+        Rectangle::new([1.0, 0.5, 0.0, 1.0]).draw(
+            [0.0, size - bar, size * state.heat_fraction(), bar],
             &ctx.draw_state,
-            ctx.transform
-                .trans(self.size / 2.0, self.size / 2.0)
-                .rot_rad(self.dir.get()),
+            ctx.transform,
             g,
         );
+        // one pip per round left, down the left edge
+        if let Some(ammo) = state.ammo {
+            let pip = size * 0.03;
+            let rows = ((size - 4.0 * bar) / (pip * 1.5)) as u32;
+            for i in 0..ammo {
+                let col = (i / rows) as f64;
+                let row = (i % rows) as f64;
+                Rectangle::new([0.0, 0.0, 0.0, 1.0]).draw(
+                    [2.0 + col * pip * 1.5, 2.0 * bar + row * pip * 1.5, pip, pip],
+                    &ctx.draw_state,
+                    ctx.transform,
+                    g,
+                );
+            }
+        }
     }
 }
 
-fn make_gun(x: f64, y: f64) -> (Shared<f64>, Box<UI<Gun>>) {
-    let orient = shared(0.0);
-    let uigun = Box::new(UI::new(
-        Gun {
-            dir: orient.clone(),
-            size: 100.0,
-        },
-        x,
-        y,
-    ));
-    (orient, uigun)
+fn make_gun(mount: Mount, fleet: SharedRef<Fleet>) -> Gun {
+    let arc = match mount {
+        Mount::Front => front_arc(),
+        Mount::Back => back_arc(),
+    };
+    let shown = fleet.clone();
+    let aimed = fleet.clone();
+    let dial = Dial::new(
+        GUN_DIAL,
+        GUN_COLOR,
+        Box::new(move || gun_controls(&shown.borrow(), mount).map(|(dir, _)| dir.get())),
+        Box::new(move |a: f64, _: &mut World| {
+            if let Some((dir, _)) = gun_controls(&aimed.borrow(), mount) {
+                dir.set(arc.clamp(a))
+            }
+        }),
+    );
+    Gun {
+        dial: dial,
+        mount: mount,
+        fleet: fleet,
+    }
 }
 
-struct ShipControl {
-    dir: Shared<f64>, // radians
-    size: f64,
+/// Dial setting the selected ships' heading
+fn make_helm(size: f64, fleet: SharedRef<Fleet>) -> Dial {
+    let shown = fleet.clone();
+    // ships already logged as not answering the helm
+    let mut reported = Vec::new();
+    Dial::new(
+        size,
+        HELM_COLOR,
+        Box::new(move || shown.borrow().selected().map(|s| s.heading.get())),
+        Box::new(move |a: f64, w: &mut World| {
+            let order = Order::Heading(a);
+            let refused = fleet.borrow().order(order);
+            report(w, order, refused, &mut reported);
+        }),
+    ).snap(HELM_SNAP)
 }
-impl Component for ShipControl {
-    fn bounding_box(&self) -> BoundingBox {
-        [0.0, 0.0, self.size, self.size]
-    }
-    fn drag(&mut self, x: f64, y: f64, _: &mut World) {
-        let v = vec2_sub(
-            [self.size, self.size],
-            [x + (self.size / 2.0), y + (self.size / 2.0)],
-        );
-        let a = vec2_angle(v);
-        if !a.is_nan() {
-            self.dir.set(a);
-        }
-    }
-    fn draw(&mut self, ctx: Context, g: &mut G2d) {
-        let bounds = [0.0, 0.0, self.size, self.size];
-        Rectangle::new([1.0; 4]).draw(bounds, &ctx.draw_state, ctx.transform, g);
-        Rectangle::new_border([0.0, 0.0, 0.0, 1.0], 1.0).draw(
-            bounds,
-            &ctx.draw_state,
-            ctx.transform,
-            g,
-        );
 
-        Rectangle::new([1.0, 0.0, 0.0, 1.0]).draw(
-            [
-                self.size * 0.8 * 0.5 * 0.5,
-                self.size * 0.05 * 0.05,
-                self.size * 0.8 * 0.5 * 0.5,
-                self.size * 0.05 * 0.05,
-            ],
-            &ctx.draw_state,
-            ctx.transform
-                .trans(self.size / 2.0, self.size / 2.0)
-                .rot_rad(self.dir.get()),
-            g,
-        );
+/// What the panel's ship is doing: its hull, speed and throttle
+fn status(s: &ShipLink) -> [String; 3] {
+    let pose = s.pose.get();
+    if !pose.alive {
+        return ["HULK".to_string(), String::new(), String::new()];
     }
+    [
+        format!("HULL {}/{}", pose.health, HULL_HEALTH),
+        format!("SPD {:.0}", pose.speed),
+        format!("THR {:.0}%", s.throttle.get() * 100.0),
+    ]
 }
 
-fn make_ship_control(x: f64, y: f64) -> (Shared<f64>, Box<UI<ShipControl>>) {
-    let orient = shared(0.0);
-    let uiship = Box::new(UI::new(
-        ShipControl {
-            dir: orient.clone(),
-            size: 500.0,
-        },
-        x,
-        y,
-    ));
-    (orient, uiship)
+/// One line of the status readout
+fn make_status_line(line: usize, fleet: SharedRef<Fleet>) -> Label {
+    Label::dynamic(
+        Box::new(move || {
+            fleet.borrow().selected().map_or(String::new(), |s| status(&s)[line].clone())
+        }),
+        SIDE_WIDTH,
+    )
 }
 
+/// Starts or stops the seat's camera following the ship the panel shows
+fn make_follow_button(seat: &Seat) -> PushButton {
+    let fleet = seat.fleet.clone();
+    let camera = seat.camera.clone();
+    PushButton::new(
+        "FOLLOW",
+        SIDE_WIDTH,
+        Box::new(move |_: &mut World| {
+            if let Some(s) = fleet.borrow().selected() {
+                update(&camera, |c| {
+                    c.follow = if c.follow == Some(s.id) { None } else { Some(s.id) }
+                });
+            }
+        }),
+    )
+}
 
+/// How a control panel is arranged and where it goes on screen
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PanelLayout {
+    /// top left corner
+    pub pos: Vector2<f64>,
+    pub heading_size: f64,
+    /// how the gun dials and the throttle line up beside the heading dial
+    pub controls: Direction,
+}
+
+/// One set of dials, bound to whichever ship the seat has selected
+pub fn make_control_panel<T: GameObjectFactory>(
+    fact: &mut T,
+    seat: &Seat,
+    layout: PanelLayout,
+) -> GameObject {
+    let fleet = &seat.fleet;
+    let guns = Panel::new(Layout::column(0.0))
+        .add(make_gun(Mount::Front, fleet.clone()))
+        .add(make_gun(Mount::Back, fleet.clone()));
+    let side = Panel::new(Layout::column(0.0))
+        .add(Label::new("THROTTLE", SIDE_WIDTH))
+        .add(make_presets(seat, SIDE_WIDTH))
+        .add(make_follow_button(seat))
+        .add(make_status_line(0, fleet.clone()))
+        .add(make_status_line(1, fleet.clone()))
+        .add(make_status_line(2, fleet.clone()));
+    let throttle = Panel::new(Layout::row(0.0))
+        .add(make_throttle(seat))
+        .add(side);
+    let mut controls = Layout::row(0.0);
+    controls.direction = layout.controls;
+    let controls = Panel::new(controls).add(guns).add(throttle);
+    let panel = Panel::new(Layout::row(0.0))
+        .add(make_helm(layout.heading_size, fleet.clone()))
+        .add(controls);
+    fact.new_gameobject()
+        .add(Box::new(UI::new(panel, layout.pos[0], layout.pos[1])))
+}
+
+/// throttle ships start at
+const CRUISE: f64 = 0.5;
+/// most a ship can turn in one turn of turn-based movement
+const TURN_LIMIT: f64 = PI / 4.0;
+/// change in velocity per second of held Thrust or Astern, as a fraction of top speed
+const THRUST: f64 = 1.0;
+/// change in ordered heading per second of held TurnLeft or TurnRight, radians
+const TURN_RATE: f64 = PI / 2.0;
+const ARC_COLOR: [f32; 4] = [0.0, 1.0, 0.0, 0.15];
+const SELECTED_COLOR: [f32; 4] = [1.0, 0.8, 0.0, 1.0];
+/// gap between a selected ship and the box drawn around it
+const SELECTED_MARGIN: f64 = 4.0;
+
+// the hull blocks a quarter turn on either side of dead astern (for the
+// front gun) or dead ahead (for the back gun)
+fn front_arc() -> FiringArc {
+    FiringArc::new(PI, 3.0 * PI / 4.0)
+}
+fn back_arc() -> FiringArc {
+    FiringArc::new(0.0, 3.0 * PI / 4.0)
+}
 
-const SPEED: f64 = 0.1;
+/// Draw `arc` as a filled wedge of the given radius around the origin of `transform`
+fn draw_arc(
+    arc: FiringArc,
+    radius: f64,
+    color: [f32; 4],
+    draw_state: &DrawState,
+    transform: Matrix2d,
+    g: &mut G2d,
+) {
+    let steps = 16;
+    let mut points = Vec::with_capacity(steps + 2);
+    points.push([0.0, 0.0]);
+    for i in 0..(steps + 1) {
+        let a = arc.start() + (arc.end() - arc.start()) * (i as f64) / (steps as f64);
+        points.push([radius * a.sin(), -radius * a.cos()]);
+    }
+    Polygon::new(color).draw(&points, draw_state, transform, g);
+}
 
 impl Ship {
-    pub fn new<T: GameObjectFactory>(fact: &mut T, sw: f64, sh: f64) -> GameObject {
-        let x_min = 500.0;
-        let (orient_front, uigun_front) = make_gun(500.0, 0.0);
-        let (orient_back, uigun_back) = make_gun(500.0, 100.0);
-        let (orient_ship, uiship) = make_ship_control(0.0, 0.0);
+    /// A ship at `pos` in the arena belonging to `player`, drawn in `color`,
+    /// taking orders from `seat` and seen through all of `cameras`
+    pub fn new<T: GameObjectFactory>(
+        fact: &mut T,
+        class: ShipClass,
+        player: PlayerId,
+        color: [f32; 4],
+        pos: Vector2<f64>,
+        seat: &Seat,
+        cameras: &Cameras,
+    ) -> GameObject {
+        let go = fact.new_gameobject();
+        let orient_front = shared(front_arc().centre);
+        let state_front = shared(GunState::new(class.front_gun));
+        let orient_back = shared(back_arc().centre);
+        let state_back = shared(GunState::new(class.back_gun));
+        let orient_ship = shared(0.0);
+        let throttle = shared(CRUISE);
         let dir = [0.0, 1.0];
-        orient_ship.set(vec2_angle(dir));
+        let pose = shared(Pose {
+            pos: pos,
+            dir: dir,
+            alive: true,
+            speed: 0.0,
+            health: HULL_HEALTH,
+        });
+        let heading = dir[1].atan2(dir[0]);
+        orient_ship.set(heading);
+        seat.fleet.borrow_mut().register(ShipLink {
+            id: go.id,
+            player: player,
+            heading: orient_ship.clone(),
+            throttle: throttle.clone(),
+            front_gun: orient_front.clone(),
+            front_arc: front_arc(),
+            front_state: state_front.clone(),
+            back_gun: orient_back.clone(),
+            back_arc: back_arc(),
+            back_state: state_back.clone(),
+            pose: pose.clone(),
+        });
         let ship = Ship {
-            color: [1.0, 0.0, 0.0, 1.0],
-            pos: [(sw - x_min) / 2.0, sh / 2.0],
+            player: player,
+            color: color,
+            pos: pos,
             orient: orient_ship,
+            heading: heading,
+            spin: 0.0,
             dir: dir,
-            speed: shared(SPEED),
+            vel: [0.0, 0.0],
+            speed: throttle,
+            engine: class.engine,
             w: 10.0,
             h: 10.0,
             orientation_front_gun: orient_front.clone(),
             front_gun: orient_front.get(),
             orientation_back_gun: orient_back.clone(),
             back_gun: orient_back.get(),
-            sw: sw,
-            sh: sh,
+            front_arc: front_arc(),
+            back_arc: back_arc(),
+            front_state: state_front,
+            back_state: state_back,
+            health: HULL_HEALTH,
+            debris: class.debris,
+            pose: pose.clone(),
+            contacts: HashSet::new(),
+            fire_orders: Vec::new(),
+            camera: seat.camera.clone(),
+            cameras: cameras.clone(),
+            seat: seat.id,
+            id: go.id,
+            fleet: seat.fleet.clone(),
         };
 
-        let boundship = Box::new(UI::new_bounds(ship, x_min, 0.0, sw - x_min, sh));
-        fact.new_gameobject()
-            .add(uigun_front)
-            .add(uigun_back)
-            .add(uiship)
-            .add(boundship)
+        let go = go.add(Box::new(Viewport::new(ship, cameras.clone())));
+        match class.point_defense {
+            Some(spec) => {
+                let pd = PointDefense::new(spec, pose, Some(player), color);
+                go.add(Box::new(Viewport::new(pd, cameras.clone())))
+            }
+            None => go,
+        }
     }
-    fn bound<T: Component>(&self, o: T) -> UI<T> {
-        let x_min = 500.0;
-        UI::new_bounds(o, x_min, 0.0, self.sw - x_min, self.sh)
+    // The ship's own frame has its origin at `pos`, the centre of the middle
+    // segment, which is also what the ship turns around. Local +y is `dir`.
+    // The front segment sits at +h and the back at -h.
+
+    /// Convert a point in the ship's frame into arena coordinates
+    fn to_world(&self, local: Vector2<f64>) -> Vector2<f64> {
+        vec2_add(self.pos, vec2_rotate(local, vec2_angle(self.dir)))
+    }
+    fn front_mount(&self) -> Vector2<f64> {
+        [0.0, self.h]
+    }
+    fn back_mount(&self) -> Vector2<f64> {
+        [0.0, -self.h]
+    }
+    /// Fire a gun at `mount` (in the ship's frame), if its arc and state allow
+    fn fire_gun(
+        &self,
+        angle: f64,
+        arc: FiringArc,
+        state: &Shared<GunState>,
+        mount: Vector2<f64>,
+        w: &mut World,
+    ) {
+        if !arc.contains(angle) || !update(state, |s| s.fire()) {
+            return;
+        }
+        let r = angle + vec2_angle(self.dir);
+        let shot = Shot {
+            owner: w.current(),
+            player: Some(self.player),
+            color: self.color,
+            pos: self.to_world(mount),
+            dir: [r.sin(), -r.cos()],
+            // range is fixed by how fast we're going when the shot leaves
+            range: range_for_speed(vec2_len(self.vel) / self.engine.max_speed),
+        };
+        fire(state.get().spec.weapon, shot, &self.cameras, w);
+    }
+    fn fire_mount(&self, mount: Mount, w: &mut World) {
+        match mount {
+            Mount::Front => {
+                let at = self.front_mount();
+                self.fire_gun(self.front_gun, self.front_arc, &self.front_state, at, w)
+            }
+            Mount::Back => {
+                let at = self.back_mount();
+                self.fire_gun(self.back_gun, self.back_arc, &self.back_state, at, w)
+            }
+        }
+    }
+    /// Fire now in real time. Otherwise the shot waits for the end of the
+    /// turn, so nobody gets to shoot while the others are still giving orders.
+    fn order_fire(&mut self, mount: Mount, w: &mut World) {
+        if w.movement() == Movement::RealTime {
+            self.fire_mount(mount, w)
+        } else if !self.fire_orders.contains(&mount) {
+            self.fire_orders.push(mount)
+        }
+    }
+    /// Push apart from any ship we overlap. Each ship takes half the separation
+    /// in its own tick, and loses whatever velocity was carrying it into the other.
+    /// Every new contact costs the ship `RAM_DAMAGE`, whichever way it was going;
+    /// the other ship charges itself the same in its own tick.
+    fn collide(&mut self, w: &mut World) {
+        let mine = match self.collider() {
+            Some(b) => b,
+            None => return,
+        };
+        let touching = w.others()
+            .filter(|c| c.collidable())
+            .filter_map(|c| {
+                c.collider().and_then(|b| contact(mine, b)).map(|ct| (c.id, c.player(), ct))
+            })
+            .collect::<Vec<_>>();
+        for &(id, player, ct) in touching.iter() {
+            self.pos = vec2_add(self.pos, vec2_scale(ct.normal, ct.depth / 2.0));
+            self.vel = vec2_cancel_into(self.vel, ct.normal);
+            if !self.contacts.contains(&id) && w.roster().may_damage(player, Some(self.player)) {
+                if let Some(me) = w.current() {
+                    w.damage(me, RAM_DAMAGE);
+                }
+            }
+        }
+        self.contacts = touching.into_iter().map(|(id, _, _)| id).collect();
+    }
+    /// End of a turn in turn-based movement: bring the guns round, apply the
+    /// throttle to our course, then move exactly `n` along it
+    fn resolve_turn(&mut self, n: f64) {
+        if self.alive() {
+            self.heading = turn_toward(self.heading, self.orient.get(), TURN_LIMIT);
+            self.dir = [self.heading.cos(), self.heading.sin()];
+            self.front_gun = self.orientation_front_gun.get();
+            self.back_gun = self.orientation_back_gun.get();
+        }
+        let throttle = if self.alive() { self.speed.get() } else { 0.0 };
+        self.vel = vec2_scale(
+            turn_velocity(self.vel, self.dir, throttle),
+            self.engine.max_speed,
+        );
+        self.pos = move_exactly(self.pos, self.vel, n);
+    }
+    /// Apply whatever the seat is holding down for `dt` seconds
+    fn held(&mut self, dt: f64, w: &World) {
+        if !self.selected() {
+            return;
+        }
+        let holding = |a| w.holding(self.seat, a);
+        let thrust = match (holding(Action::Thrust), holding(Action::Astern)) {
+            (true, false) => 1.0,
+            (false, true) => -1.0,
+            _ => 0.0,
+        };
+        let turn = match (holding(Action::TurnLeft), holding(Action::TurnRight)) {
+            (true, false) => -1.0,
+            (false, true) => 1.0,
+            _ => 0.0,
+        };
+        if thrust != 0.0 {
+            let max = self.engine.max_speed;
+            self.vel = held_thrust(self.vel, self.dir, thrust * THRUST, max, dt);
+        }
+        if turn != 0.0 {
+            update(&self.orient, |h| *h += turn * TURN_RATE * dt);
+        }
+    }
+    fn alive(&self) -> bool {
+        self.health > 0
+    }
+    fn selected(&self) -> bool {
+        self.fleet.borrow().is_selected(self.id)
+    }
+    fn primary(&self) -> bool {
+        self.fleet.borrow().is_primary(self.id)
+    }
+    /// Turn into a hulk: the controls go dead but the ship keeps drifting
+    fn wreck(&mut self, w: &mut World) {
+        let me = w.current();
+        w.log(format!("ship {} destroyed", me.unwrap_or(0)));
+        let vel = self.vel;
+        for _ in 0..self.debris {
+            let a = w.random() * 2.0 * PI;
+            let kick = vec2_scale([a.cos(), a.sin()], DEBRIS_SCATTER * (0.5 + w.random()));
+            let piece = Debris::new(
+                HULK_COLOR,
+                vec2_add(self.pos, vec2_scale(kick, self.h * 3.0)),
+                vec2_add(vel, kick),
+                me,
+            );
+            w.spawn_comp(Viewport::new(piece, self.cameras.clone()));
+        }
     }
 }
 impl Component for Ship {
+    fn collidable(&self) -> bool {
+        true
+    }
+    fn collider(&self) -> Option<BoundingBox> {
+        let half = [self.w / 2.0, self.h * 1.5];
+        Some(bb_rotated(self.pos, half, vec2_angle(self.dir)))
+    }
+    fn player(&self) -> Option<PlayerId> {
+        Some(self.player)
+    }
+    fn blip(&self) -> Option<Blip> {
+        Some(Blip {
+            kind: BlipKind::Ship,
+            pos: self.pos,
+            color: if self.alive() { self.color } else { HULK_COLOR },
+        })
+    }
+    fn hit(&mut self, damage: u32, w: &mut World) {
+        if !self.alive() {
+            return;
+        }
+        self.health = self.health.saturating_sub(damage);
+        if !self.alive() {
+            self.wreck(w);
+        }
+    }
     fn tick(&mut self, a: &UpdateArgs, w: &mut World) {
-        self.dir[0] = self.orient.get().cos();
-        self.dir[1] = self.orient.get().sin();
-        self.pos = vec2_add(self.pos, vec2_scale(self.dir, self.speed.get()));
-        self.pos[0] = f64::max(f64::min(self.pos[0], 640.0 - self.w), 0.0);
-        self.pos[1] = f64::max(f64::min(self.pos[1], 480.0 - self.h), 0.0);
+        if self.alive() {
+            self.held(a.dt, w);
+        }
+        if self.alive() && w.movement() == Movement::RealTime {
+            let (heading, spin) =
+                steer(self.heading, self.spin, self.orient.get(), self.engine, a.dt);
+            self.heading = heading;
+            self.spin = spin;
+        }
+        self.dir = [self.heading.cos(), self.heading.sin()];
+        if w.movement() == Movement::RealTime {
+            let throttle = if self.alive() { self.speed.get() } else { 0.0 };
+            let (pos, vel) = integrate(self.pos, self.vel, self.dir, throttle, self.engine, a.dt);
+            self.pos = pos;
+            self.vel = vel;
+        }
+        match w.arena().confine(self.pos, self.vel) {
+            Some((pos, vel)) => {
+                self.pos = pos;
+                self.vel = vel;
+            }
+            None => {
+                let me = w.current().unwrap_or(0);
+                w.log(format!("ship {} disengaged", me));
+                self.fleet.borrow_mut().remove(self.id);
+                w.destroy_self();
+                return;
+            }
+        }
+        self.collide(w);
+        let pos = self.pos;
+        if w.current().is_some() && self.camera.get().follow == w.current() {
+            update(&self.camera, |c| c.centre = pos);
+        }
 
-        self.front_gun = self.orientation_front_gun.get();
-        self.back_gun = self.orientation_back_gun.get();
-    }
-    fn press(&mut self, b: &Button, w: &mut World) {
-        match b {
-            &Keyboard(k) => {
-                match k {
-                    Key::W => self.dir = vec2_max_add(self.dir, [0.0, -SPEED], 1.0),
-                    Key::S => self.dir = vec2_max_add(self.dir, [0.0, SPEED], 1.0),
-                    Key::A => self.dir = vec2_max_add(self.dir, [-SPEED, 0.0], 1.0),
-                    Key::D => self.dir = vec2_max_add(self.dir, [SPEED, 0.0], 1.0),
-                    _ => (),
-                }
+        self.pose.set(Pose {
+            pos: self.pos,
+            dir: self.dir,
+            alive: self.alive(),
+            speed: vec2_len(self.vel),
+            health: self.health,
+        });
+        if !self.alive() {
+            return;
+        }
+
+        // in turn-based play the guns only move when the turn resolves,
+        // so nobody sees where the other side has ordered them
+        if w.movement() == Movement::RealTime {
+            self.front_gun = self.orientation_front_gun.get();
+            self.back_gun = self.orientation_back_gun.get();
+        }
+        update(&self.front_state, |s| s.tick(a.dt));
+        update(&self.back_state, |s| s.tick(a.dt));
+    }
+    fn end_turn(&mut self, w: &mut World) {
+        if let Movement::PerTurn(n) = w.movement() {
+            self.resolve_turn(n)
+        }
+        let orders = ::std::mem::replace(&mut self.fire_orders, Vec::new());
+        if self.alive() {
+            for mount in orders {
+                self.fire_mount(mount, w);
             }
-            _ => (),
         }
     }
-    fn release(&mut self, b: &Button, w: &mut World) {
-        match b {
-            &Keyboard(k) => {
-                match k {
-                    Key::Space => {
-                        let r = vec2_angle(self.dir);
-                        let front_r = self.front_gun + r;
-                        let back_r = self.back_gun + r;
-                        let b1 = self.bound(Bullet::new(
-                            self.color,
-                            vec2_add(self.pos,vec2_scale(self.dir,self.w)),
-                            vec2_scale(
-                                [front_r.sin(), -front_r.cos()],
-                                2.0,
-                            ),
-                        ));
-                        let b2 = self.bound(Bullet::new(
-                            self.color,
-                            vec2_add(self.pos,vec2_scale(self.dir,-self.w)),
-                            vec2_scale([back_r.sin(), -back_r.cos()], 2.0),
-                        ));
-                        w.spawn_comp(b1);
-                        w.spawn_comp(b2);
-                    }
-                    _ => (),
-                }
+    fn action(&mut self, seat: usize, action: Action, w: &mut World) {
+        if seat != self.seat || !self.alive() || !self.selected() {
+            return;
+        }
+        match action {
+            Action::FireFront => self.order_fire(Mount::Front, w),
+            Action::FireBack => self.order_fire(Mount::Back, w),
+            Action::Follow if self.primary() => {
+                let me = w.current();
+                update(&self.camera, |c| {
+                    c.follow = if c.follow == me { None } else { me }
+                });
             }
             _ => (),
         }
@@ -235,23 +767,49 @@ impl Component for Ship {
                 self.dir,
             ),
         );
-        rectangle(self.color, [0.0, 0.0, self.w, self.h], transform, g);
-        rectangle(self.color, [0.0, self.h, self.w, self.h], transform, g);
-        rectangle(self.color, [0.0, -self.h, self.w, self.h], transform, g);
-        rectangle(
-            [0.0, 1.0, 0.0, 1.0],
-            [0.0, 0.0, (self.w / 4.0), -(self.h)],
-            transform
-                .append_transform(translate([(self.w / 2.0), (self.h / 2.0) + self.h]))
-                .append_transform(rotate_radians(self.front_gun)),
+        let color = if self.alive() {
+            self.color
+        } else {
+            HULK_COLOR
+        };
+        if self.selected() {
+            if let Some(b) = self.collider() {
+                let m = SELECTED_MARGIN;
+                // the ship the control panel shows gets the heavier box
+                let weight = if self.primary() { 2.0 } else { 1.0 };
+                Rectangle::new_border(SELECTED_COLOR, weight).draw(
+                    [b[0] - m, b[1] - m, b[2] + 2.0 * m, b[3] + 2.0 * m],
+                    &ctx.draw_state,
+                    ctx.transform,
+                    g,
+                );
+            }
+        }
+        let (x, y) = (-self.w / 2.0, -self.h / 2.0);
+        let ds = &ctx.draw_state;
+        let hull = Rectangle::new(color);
+        hull.draw([x, y, self.w, self.h], ds, transform, g);
+        hull.draw([x, y + self.h, self.w, self.h], ds, transform, g);
+        hull.draw([x, y - self.h, self.w, self.h], ds, transform, g);
+        if !self.alive() {
+            return;
+        }
+        let front = transform.append_transform(translate(self.front_mount()));
+        let back = transform.append_transform(translate(self.back_mount()));
+        draw_arc(self.front_arc, self.h * 4.0, ARC_COLOR, ds, front, g);
+        draw_arc(self.back_arc, self.h * 4.0, ARC_COLOR, ds, back, g);
+        let barrel = [-self.w / 8.0, 0.0, (self.w / 4.0), -(self.h)];
+        let gun = Rectangle::new([0.0, 1.0, 0.0, 1.0]);
+        gun.draw(
+            barrel,
+            ds,
+            front.append_transform(rotate_radians(self.front_gun)),
             g,
         );
-        rectangle(
-            [0.0, 1.0, 0.0, 1.0],
-            [0.0, 0.0, (self.w / 4.0), -(self.h)],
-            transform
-                .append_transform(translate([(self.w / 2.0), (self.h / 2.0) - self.h]))
-                .append_transform(rotate_radians(self.back_gun)),
+        gun.draw(
+            barrel,
+            ds,
+            back.append_transform(rotate_radians(self.back_gun)),
             g,
         );
     }
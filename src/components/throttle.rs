@@ -0,0 +1,119 @@
+use components::*;
+use piston_window::*;
+use common::*;
+use super::fleet::*;
+use super::seat::*;
+use super::widget::*;
+use bindings::*;
+
+pub const MAX_THROTTLE: f64 = 1.0;
+pub const MAX_REVERSE: f64 = -0.5;
+/// positions the slider sticks to
+const DETENTS: [f64; 3] = [MAX_REVERSE, 0.0, MAX_THROTTLE];
+/// how close a drag has to come to a detent to stick to it
+const DETENT_SNAP: f64 = 0.05;
+/// change from one Faster or Slower
+const STEP: f64 = 0.1;
+/// settings the preset buttons jump to
+const PRESETS: [(&'static str, f64); 4] = [
+    ("FULL", MAX_THROTTLE),
+    ("HALF", MAX_THROTTLE / 2.0),
+    ("STOP", 0.0),
+    ("REV", MAX_REVERSE),
+];
+
+/// The throttle of the ship the panel shows
+fn shown(fleet: &SharedRef<Fleet>) -> Box<Fn() -> Option<f64>> {
+    let fleet = fleet.clone();
+    Box::new(move || fleet.borrow().selected().map(|s| s.throttle.get()))
+}
+
+/// Orders the selected ships to a throttle setting
+fn orders(fleet: &SharedRef<Fleet>) -> Box<FnMut(f64, &mut World)> {
+    let fleet = fleet.clone();
+    // ships already logged as not answering the throttle
+    let mut reported = Vec::new();
+    Box::new(move |v: f64, w: &mut World| {
+        let order = Order::Throttle(v);
+        let refused = fleet.borrow().order(order);
+        report(w, order, refused, &mut reported);
+    })
+}
+
+/// A vertical slider setting the selected ships' throttle. The seat's
+/// actions and the mouse wheel step it, or jump it to all stop, full ahead
+/// and full reverse.
+pub struct Throttle {
+    seat: usize,
+    slider: Slider,
+}
+
+impl Component for Throttle {
+    fn bounding_box(&self) -> BoundingBox {
+        self.slider.bounding_box()
+    }
+    fn drag_start(&mut self, x: f64, y: f64, w: &mut World) {
+        self.slider.drag_start(x, y, w)
+    }
+    fn drag(&mut self, x: f64, y: f64, w: &mut World) {
+        self.slider.drag(x, y, w)
+    }
+    fn scroll(&mut self, _: f64, _: f64, dy: f64, w: &mut World) {
+        if let Some(v) = self.slider.value() {
+            self.slider.set_value(v + dy * STEP, w);
+        }
+    }
+    fn set_focus(&mut self, focused: bool) {
+        self.slider.set_focus(focused)
+    }
+    fn focus_key(&mut self, b: &Button, w: &mut World) -> bool {
+        self.slider.focus_key(b, w)
+    }
+    fn action(&mut self, seat: usize, action: Action, w: &mut World) {
+        let v = match self.slider.value() {
+            Some(v) if seat == self.seat => v,
+            _ => return,
+        };
+        let v = match action {
+            Action::Faster => v + STEP,
+            Action::Slower => v - STEP,
+            Action::AllStop => 0.0,
+            Action::FullAhead => MAX_THROTTLE,
+            Action::FullReverse => MAX_REVERSE,
+            _ => return,
+        };
+        self.slider.set_value(v, w);
+    }
+    fn draw(&mut self, ctx: Context, g: &mut G2d) {
+        self.slider.draw(ctx, g)
+    }
+}
+
+pub fn make_throttle(seat: &Seat) -> Throttle {
+    let slider = Slider::new(
+        30.0,
+        200.0,
+        (MAX_REVERSE, MAX_THROTTLE),
+        shown(&seat.fleet),
+        orders(&seat.fleet),
+    ).detents(DETENTS.to_vec(), DETENT_SNAP)
+        .step(STEP);
+    Throttle {
+        seat: seat.id,
+        slider: slider,
+    }
+}
+
+/// Buttons jumping the throttle to the usual settings, lit when it's at one
+pub fn make_presets(seat: &Seat, w: f64) -> ToggleGroup {
+    let throttle = shown(&seat.fleet);
+    let mut order = orders(&seat.fleet);
+    ToggleGroup::new(
+        PRESETS.iter().map(|p| p.0).collect(),
+        w,
+        Box::new(move || {
+            throttle().and_then(|v| PRESETS.iter().position(|p| (p.1 - v).abs() < 1e-9))
+        }),
+        Box::new(move |i: usize, w: &mut World| order(PRESETS[i].1, w)),
+    )
+}
@@ -3,11 +3,17 @@ use components::*;
 use piston_window::*;
 use vecmath::*;
 use collisions::*;
+use team::*;
+use bindings::*;
 use graphics::math::*;
 
 pub struct UI<T: Component> {
     obj: T,
     bounds: BoundingBox,
+    /// is the pointer inside the bounds?
+    hovered: bool,
+    /// did the current drag start here? If so it stays here until it ends.
+    dragging: bool,
 }
 
 impl<T: Component> UI<T> {
@@ -16,12 +22,16 @@ impl<T: Component> UI<T> {
         UI {
             obj: obj,
             bounds: [x, y, bb[2], bb[3]],
+            hovered: false,
+            dragging: false,
         }
     }
 pub fn new_bounds(obj: T, x: f64, y: f64,w:f64,h:f64) -> UI<T> {
         UI {
             obj: obj,
             bounds: [x, y, w, h],
+            hovered: false,
+            dragging: false,
         }
     }
 }
@@ -36,16 +46,60 @@ impl<T: Component> Component for UI<T> {
             g,
         )
     }
-    fn drag(&mut self, x: f64, y: f64, w: &mut World) {
+    fn drag_start(&mut self, x: f64, y: f64, w: &mut World) {
         if collides_point(self.bounds, [x, y]) {
+            self.dragging = true;
+            self.obj.drag_start(x - self.bounds[0], y - self.bounds[1], w)
+        } else {
+            // pressing anywhere else takes the keyboard away
+            self.obj.set_focus(false)
+        }
+    }
+    fn drag(&mut self, x: f64, y: f64, w: &mut World) {
+        if self.dragging {
             self.obj.drag(x - self.bounds[0], y - self.bounds[1], w)
         }
     }
+    fn drag_end(&mut self, x: f64, y: f64, w: &mut World) {
+        if self.dragging {
+            self.dragging = false;
+            self.obj.drag_end(x - self.bounds[0], y - self.bounds[1], w)
+        }
+    }
+    fn pointer_move(&mut self, x: f64, y: f64, w: &mut World) {
+        let inside = collides_point(self.bounds, [x, y]);
+        let (x, y) = (x - self.bounds[0], y - self.bounds[1]);
+        if inside && !self.hovered {
+            self.obj.hover_enter(x, y, w);
+        } else if !inside && self.hovered {
+            self.obj.hover_leave(w);
+        }
+        self.hovered = inside;
+        if inside {
+            self.obj.pointer_move(x, y, w)
+        }
+    }
+    fn scroll(&mut self, x: f64, y: f64, dy: f64, w: &mut World) {
+        if collides_point(self.bounds, [x, y]) {
+            self.obj.scroll(x - self.bounds[0], y - self.bounds[1], dy, w)
+        }
+    }
+    fn context_click(&mut self, x: f64, y: f64, w: &mut World) {
+        if collides_point(self.bounds, [x, y]) {
+            self.obj.context_click(x - self.bounds[0], y - self.bounds[1], w)
+        }
+    }
     fn click(&mut self, x: f64, y: f64, w: &mut World) {
         if collides_point(self.bounds, [x, y]) {
             self.obj.click(x - self.bounds[0], y - self.bounds[1], w)
         }
     }
+    fn set_focus(&mut self, focused: bool) {
+        self.obj.set_focus(focused)
+    }
+    fn focus_key(&mut self, b: &Button, w: &mut World) -> bool {
+        self.obj.focus_key(b, w)
+    }
     fn tick(&mut self, u: &UpdateArgs, w: &mut World) {
         self.obj.tick(u, w)
     }
@@ -58,4 +112,32 @@ impl<T: Component> Component for UI<T> {
     fn destroy(&mut self, w: &mut World) {
         self.obj.destroy(w)
     }
+    fn hit(&mut self, damage: u32, w: &mut World) {
+        self.obj.hit(damage, w)
+    }
+    fn end_turn(&mut self, w: &mut World) {
+        self.obj.end_turn(w)
+    }
+    fn action(&mut self, seat: usize, action: Action, w: &mut World) {
+        self.obj.action(seat, action, w)
+    }
+    fn collidable(&self) -> bool {
+        self.obj.collidable()
+    }
+    // colliders are already in arena coordinates, so they aren't offset by the bounds
+    fn collider(&self) -> Option<BoundingBox> {
+        self.obj.collider()
+    }
+    fn owner(&self) -> Option<UUID> {
+        self.obj.owner()
+    }
+    fn interceptable(&self) -> bool {
+        self.obj.interceptable()
+    }
+    fn blip(&self) -> Option<Blip> {
+        self.obj.blip()
+    }
+    fn player(&self) -> Option<PlayerId> {
+        self.obj.player()
+    }
 }
\ No newline at end of file
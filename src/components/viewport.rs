@@ -0,0 +1,197 @@
+use components::*;
+use piston_window::*;
+use collisions::*;
+use team::*;
+use bindings::*;
+use camera::*;
+
+/// how far outside a camera's view something can be and still get drawn
+/// in it, in arena units
+const CULL_MARGIN: f64 = 50.0;
+
+/// The part of the window `view` covers, in whole pixels, to clip drawing to
+fn scissor(view: BoundingBox) -> [u32; 4] {
+    let x = view[0].max(0.0);
+    let y = view[1].max(0.0);
+    let w = (view[0] + view[2] - x).max(0.0);
+    let h = (view[1] + view[3] - y).max(0.0);
+    [x as u32, y as u32, w as u32, h as u32]
+}
+
+/// Like `UI`, but for things that live in the arena: drawing goes through
+/// every camera, and clicks and drags inside a camera's view arrive in
+/// arena coordinates
+pub struct Viewport<T: Component> {
+    obj: T,
+    cameras: Cameras,
+    /// is the pointer over the thing's collider?
+    hovered: bool,
+    /// the camera the current drag started in, if it started on this
+    dragging: Option<usize>,
+}
+
+impl<T: Component> Viewport<T> {
+    pub fn new(obj: T, cameras: Cameras) -> Viewport<T> {
+        Viewport {
+            obj: obj,
+            cameras: cameras,
+            hovered: false,
+            dragging: None,
+        }
+    }
+    /// The camera whose view holds the screen point, if any
+    fn camera_at(&self, x: f64, y: f64) -> Option<Camera> {
+        self.camera_index_at(x, y).and_then(|i| self.camera(i))
+    }
+    fn camera_index_at(&self, x: f64, y: f64) -> Option<usize> {
+        self.cameras
+            .borrow()
+            .iter()
+            .position(|c| collides_point(c.get().view, [x, y]))
+    }
+    fn camera(&self, i: usize) -> Option<Camera> {
+        self.cameras.borrow().get(i).map(|c| c.get())
+    }
+    /// The screen point in the arena, through whichever camera it's in
+    fn to_world(&self, x: f64, y: f64) -> Option<[f64; 2]> {
+        self.camera_at(x, y).map(|cam| cam.screen_to_world([x, y]))
+    }
+    /// Could this be seen by `cam`? Drawing is clipped to the view anyway,
+    /// this only saves drawing what's well outside it.
+    fn in_view(&self, cam: &Camera) -> bool {
+        let at = self.obj
+            .blip()
+            .map(|b| b.pos)
+            .or_else(|| self.obj.collider().map(bb_center));
+        match at {
+            Some(p) => {
+                let v = cam.visible();
+                let m = CULL_MARGIN;
+                collides_point([v[0] - m, v[1] - m, v[2] + 2.0 * m, v[3] + 2.0 * m], p)
+            }
+            None => true,
+        }
+    }
+}
+
+impl<T: Component> Component for Viewport<T> {
+    fn bounding_box(&self) -> BoundingBox {
+        self.cameras.borrow().first().map_or([0.0; 4], |c| c.get().view)
+    }
+    fn draw(&mut self, c: Context, g: &mut G2d) {
+        let cameras = self.cameras.borrow().clone();
+        for cam in cameras.iter().map(|c| c.get()) {
+            if self.in_view(&cam) {
+                // split-screen views mustn't draw into each other
+                let mut ctx = c.append_transform(cam.matrix());
+                ctx.draw_state = ctx.draw_state.scissor(scissor(cam.view));
+                self.obj.draw(ctx, g)
+            }
+        }
+    }
+    // only a press on the thing itself starts dragging it
+    fn drag_start(&mut self, x: f64, y: f64, w: &mut World) {
+        let i = match self.camera_index_at(x, y) {
+            Some(i) => i,
+            None => return,
+        };
+        let p = match self.camera(i) {
+            Some(cam) => cam.screen_to_world([x, y]),
+            None => return,
+        };
+        if self.obj.collider().map_or(false, |bb| collides_point(bb, p)) {
+            self.dragging = Some(i);
+            self.obj.drag_start(p[0], p[1], w)
+        }
+    }
+    // a drag stays with the camera it started in, even outside its view
+    fn drag(&mut self, x: f64, y: f64, w: &mut World) {
+        if let Some(cam) = self.dragging.and_then(|i| self.camera(i)) {
+            let p = cam.screen_to_world([x, y]);
+            self.obj.drag(p[0], p[1], w)
+        }
+    }
+    fn drag_end(&mut self, x: f64, y: f64, w: &mut World) {
+        if let Some(cam) = self.dragging.take().and_then(|i| self.camera(i)) {
+            let p = cam.screen_to_world([x, y]);
+            self.obj.drag_end(p[0], p[1], w)
+        }
+    }
+    fn pointer_move(&mut self, x: f64, y: f64, w: &mut World) {
+        let p = self.to_world(x, y);
+        let inside = match (p, self.obj.collider()) {
+            (Some(p), Some(bb)) => collides_point(bb, p),
+            _ => false,
+        };
+        if inside && !self.hovered {
+            let p = p.unwrap();
+            self.obj.hover_enter(p[0], p[1], w);
+        } else if !inside && self.hovered {
+            self.obj.hover_leave(w);
+        }
+        self.hovered = inside;
+        if let Some(p) = p {
+            self.obj.pointer_move(p[0], p[1], w)
+        }
+    }
+    fn click(&mut self, x: f64, y: f64, w: &mut World) {
+        if let Some(p) = self.to_world(x, y) {
+            self.obj.click(p[0], p[1], w)
+        }
+    }
+    fn scroll(&mut self, x: f64, y: f64, dy: f64, w: &mut World) {
+        if let Some(p) = self.to_world(x, y) {
+            self.obj.scroll(p[0], p[1], dy, w)
+        }
+    }
+    fn context_click(&mut self, x: f64, y: f64, w: &mut World) {
+        if let Some(p) = self.to_world(x, y) {
+            self.obj.context_click(p[0], p[1], w)
+        }
+    }
+    fn set_focus(&mut self, focused: bool) {
+        self.obj.set_focus(focused)
+    }
+    fn focus_key(&mut self, b: &Button, w: &mut World) -> bool {
+        self.obj.focus_key(b, w)
+    }
+    fn tick(&mut self, u: &UpdateArgs, w: &mut World) {
+        self.obj.tick(u, w)
+    }
+    fn press(&mut self, b: &Button, w: &mut World) {
+        self.obj.press(b, w)
+    }
+    fn release(&mut self, b: &Button, w: &mut World) {
+        self.obj.release(b, w)
+    }
+    fn destroy(&mut self, w: &mut World) {
+        self.obj.destroy(w)
+    }
+    fn hit(&mut self, damage: u32, w: &mut World) {
+        self.obj.hit(damage, w)
+    }
+    fn end_turn(&mut self, w: &mut World) {
+        self.obj.end_turn(w)
+    }
+    fn action(&mut self, seat: usize, action: Action, w: &mut World) {
+        self.obj.action(seat, action, w)
+    }
+    fn collidable(&self) -> bool {
+        self.obj.collidable()
+    }
+    fn collider(&self) -> Option<BoundingBox> {
+        self.obj.collider()
+    }
+    fn owner(&self) -> Option<UUID> {
+        self.obj.owner()
+    }
+    fn interceptable(&self) -> bool {
+        self.obj.interceptable()
+    }
+    fn blip(&self) -> Option<Blip> {
+        self.obj.blip()
+    }
+    fn player(&self) -> Option<PlayerId> {
+        self.obj.player()
+    }
+}
@@ -0,0 +1,59 @@
+use components::*;
+use vecmath::*;
+use collisions::*;
+use turret::*;
+use super::viewport::*;
+use camera::*;
+use team::*;
+use super::bullet::*;
+use super::missile::*;
+use super::beam::*;
+use super::flak::*;
+
+/// Everything a projectile needs to know about the moment it was fired
+#[derive(Clone, Copy, Debug)]
+pub struct Shot {
+    pub owner: Option<UUID>,
+    /// the player whose ship fired it
+    pub player: Option<PlayerId>,
+    pub color: [f32; 4],
+    pub pos: Vector2<f64>,
+    /// unit vector
+    pub dir: Vector2<f64>,
+    pub range: f64,
+}
+
+/// Is `c` the shooter, or something else the shooter fired?
+pub fn owned_by(c: &GameObject, owner: Option<UUID>) -> bool {
+    owner.is_some() && (Some(c.id) == owner || c.owner() == owner)
+}
+
+/// Everything touching `bb` that doesn't belong to `owner`
+/// and that `player` is allowed to hurt
+pub fn hits(
+    w: &World,
+    bb: BoundingBox,
+    owner: Option<UUID>,
+    player: Option<PlayerId>,
+) -> Vec<UUID> {
+    w.collisions(bb)
+        .into_iter()
+        .filter(|c| !owned_by(c, owner))
+        .filter(|c| w.roster().may_damage(player, c.player()))
+        .map(|c| c.id)
+        .collect()
+}
+
+/// Spawn whatever `kind` of weapon fires, seen through `cameras`
+pub fn fire(kind: WeaponKind, shot: Shot, cameras: &Cameras, w: &mut World) {
+    let cam = cameras.clone();
+    match kind {
+        WeaponKind::Cannon => w.spawn_comp(Viewport::new(Bullet::new(shot), cam)),
+        WeaponKind::Missile => {
+            let target = w.nearest(shot.pos, shot.owner, shot.player).map(|(id, _)| id);
+            w.spawn_comp(Viewport::new(Missile::new(shot, target), cam))
+        }
+        WeaponKind::Laser => w.spawn_comp(Viewport::new(Beam::new(shot), cam)),
+        WeaponKind::Flak => w.spawn_comp(Viewport::new(Flak::new(shot), cam)),
+    }
+}
@@ -0,0 +1,781 @@
+use components::*;
+use piston_window::*;
+use vecmath::*;
+use collisions::*;
+use bindings::*;
+use layout::*;
+use math::*;
+use font::*;
+use std::f64::consts::PI;
+
+const FACE: [f32; 4] = [1.0; 4];
+const HOVER_FACE: [f32; 4] = [0.9, 0.9, 1.0, 1.0];
+const EDGE: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+/// edge of a widget with nothing to control
+const DEAD_EDGE: [f32; 4] = [0.5, 0.5, 0.5, 1.0];
+const FOCUS_COLOR: [f32; 4] = [0.2, 0.4, 1.0, 1.0];
+/// fill of the chosen option in a toggle group
+const CHOSEN: [f32; 4] = [0.6, 0.8, 1.0, 1.0];
+const FORWARD_COLOR: [f32; 4] = [0.0, 1.0, 0.0, 1.0];
+const REVERSE_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+pub const TEXT_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+/// height of a row of text, label or button
+pub const LINE: f64 = 20.0;
+/// how far a key press turns a dial that doesn't snap
+const DIAL_KEY_STEP: f64 = PI / 36.0;
+
+/// Write `text` at (x, y) at the font's own size
+pub fn write(text: &str, x: f64, y: f64, color: [f32; 4], ctx: Context, g: &mut G2d) {
+    draw_text(text, [x, y], 1.0, color, &ctx.draw_state, ctx.transform, g)
+}
+
+/// `text` in the middle of `bounds`
+fn draw_centred(text: &str, bounds: BoundingBox, ctx: Context, g: &mut G2d) {
+    let x = bounds[0] + (bounds[2] - text_width(text, 1.0)) / 2.0;
+    let y = bounds[1] + (bounds[3] - HEIGHT) / 2.0;
+    write(text, x, y, TEXT_COLOR, ctx, g);
+}
+
+/// The usual background and border, with a ring round it when focused
+fn frame(
+    bounds: BoundingBox,
+    face: [f32; 4],
+    edge: [f32; 4],
+    focused: bool,
+    ctx: Context,
+    g: &mut G2d,
+) {
+    Rectangle::new(face).draw(bounds, &ctx.draw_state, ctx.transform, g);
+    Rectangle::new_border(edge, 1.0).draw(bounds, &ctx.draw_state, ctx.transform, g);
+    if focused {
+        Rectangle::new_border(FOCUS_COLOR, 1.0).draw(
+            [bounds[0] + 2.0, bounds[1] + 2.0, bounds[2] - 4.0, bounds[3] - 4.0],
+            &ctx.draw_state,
+            ctx.transform,
+            g,
+        );
+    }
+}
+
+/// A line of text
+pub struct Label {
+    text: Box<Fn() -> String>,
+    w: f64,
+}
+
+impl Label {
+    pub fn new(text: &'static str, w: f64) -> Label {
+        Label::dynamic(Box::new(move || text.to_string()), w)
+    }
+    /// A label showing whatever `text` says at the time
+    pub fn dynamic(text: Box<Fn() -> String>, w: f64) -> Label {
+        Label { text: text, w: w }
+    }
+}
+
+impl Component for Label {
+    fn bounding_box(&self) -> BoundingBox {
+        [0.0, 0.0, self.w, LINE]
+    }
+    fn draw(&mut self, ctx: Context, g: &mut G2d) {
+        let text = (self.text)();
+        write(&text, 2.0, (LINE - HEIGHT) / 2.0, TEXT_COLOR, ctx, g);
+    }
+}
+
+/// Does something when clicked. It doesn't take the keyboard, so pressing
+/// it leaves Space and Return to their actions.
+pub struct PushButton {
+    label: &'static str,
+    w: f64,
+    on_press: Box<FnMut(&mut World)>,
+    hovered: bool,
+}
+
+impl PushButton {
+    pub fn new(label: &'static str, w: f64, on_press: Box<FnMut(&mut World)>) -> PushButton {
+        PushButton {
+            label: label,
+            w: w,
+            on_press: on_press,
+            hovered: false,
+        }
+    }
+}
+
+impl Component for PushButton {
+    fn bounding_box(&self) -> BoundingBox {
+        [0.0, 0.0, self.w, LINE]
+    }
+    fn click(&mut self, _: f64, _: f64, w: &mut World) {
+        (self.on_press)(w)
+    }
+    fn hover_enter(&mut self, _: f64, _: f64, _: &mut World) {
+        self.hovered = true
+    }
+    fn hover_leave(&mut self, _: &mut World) {
+        self.hovered = false
+    }
+    fn draw(&mut self, ctx: Context, g: &mut G2d) {
+        let bounds = self.bounding_box();
+        let face = if self.hovered { HOVER_FACE } else { FACE };
+        frame(bounds, face, EDGE, false, ctx, g);
+        draw_centred(self.label, bounds, ctx, g);
+    }
+}
+
+/// A round dial setting an angle by dragging its needle. With `snap` it
+/// sticks to multiples of that, and the arrow keys turn it while focused.
+pub struct Dial {
+    size: f64,
+    color: [f32; 4],
+    value: Box<Fn() -> Option<f64>>,
+    set: Box<FnMut(f64, &mut World)>,
+    snap: Option<f64>,
+    focused: bool,
+}
+
+impl Dial {
+    /// `value` is the angle to show, if there's anything to control, and
+    /// `set` is told the angle the player picks
+    pub fn new(
+        size: f64,
+        color: [f32; 4],
+        value: Box<Fn() -> Option<f64>>,
+        set: Box<FnMut(f64, &mut World)>,
+    ) -> Dial {
+        Dial {
+            size: size,
+            color: color,
+            value: value,
+            set: set,
+            snap: None,
+            focused: false,
+        }
+    }
+    pub fn snap(mut self, step: f64) -> Dial {
+        self.snap = Some(step);
+        self
+    }
+    pub fn value(&self) -> Option<f64> {
+        (self.value)()
+    }
+    fn set_angle(&mut self, a: f64, w: &mut World) {
+        if a.is_nan() || self.value().is_none() {
+            return;
+        }
+        let a = match self.snap {
+            Some(step) => snap_angle(a, step),
+            None => a,
+        };
+        (self.set)(a, w)
+    }
+    fn angle_at(&self, x: f64, y: f64) -> f64 {
+        let half = self.size / 2.0;
+        vec2_angle(vec2_sub([half, half], [x, y]))
+    }
+    /// The face and needle, for widgets that draw more on top
+    pub fn draw_dial(&self, edge: [f32; 4], ctx: Context, g: &mut G2d) {
+        let bounds = self.bounding_box();
+        let value = self.value();
+        let edge = if value.is_some() { edge } else { DEAD_EDGE };
+        frame(bounds, FACE, edge, self.focused, ctx, g);
+        let inset = self.size * 0.1;
+        Ellipse::new_border(edge, 0.5).draw(
+            [inset, inset, self.size - 2.0 * inset, self.size - 2.0 * inset],
+            &ctx.draw_state,
+            ctx.transform,
+            g,
+        );
+        if let Some(a) = value {
+            Rectangle::new(self.color).draw(
+                [0.0, 0.0, self.size * 0.8 * 0.5, self.size * 0.05],
+                &ctx.draw_state,
+                ctx.transform.trans(self.size / 2.0, self.size / 2.0).rot_rad(a),
+                g,
+            );
+        }
+    }
+}
+
+impl Component for Dial {
+    fn bounding_box(&self) -> BoundingBox {
+        [0.0, 0.0, self.size, self.size]
+    }
+    fn drag_start(&mut self, x: f64, y: f64, w: &mut World) {
+        let a = self.angle_at(x, y);
+        self.set_angle(a, w)
+    }
+    fn drag(&mut self, x: f64, y: f64, w: &mut World) {
+        let a = self.angle_at(x, y);
+        self.set_angle(a, w)
+    }
+    fn set_focus(&mut self, focused: bool) {
+        self.focused = focused
+    }
+    fn focus_key(&mut self, b: &Button, w: &mut World) -> bool {
+        let step = self.snap.unwrap_or(DIAL_KEY_STEP);
+        let turn = match *b {
+            Button::Keyboard(Key::Left) => -step,
+            Button::Keyboard(Key::Right) => step,
+            _ => return false,
+        };
+        if let Some(a) = self.value() {
+            self.set_angle(a + turn, w);
+        }
+        true
+    }
+    fn draw(&mut self, ctx: Context, g: &mut G2d) {
+        self.draw_dial(EDGE, ctx, g)
+    }
+}
+
+/// A vertical slider between `min` (bottom) and `max` (top) that sticks
+/// to its detents. The up and down keys step it while focused.
+pub struct Slider {
+    w: f64,
+    h: f64,
+    min: f64,
+    max: f64,
+    detents: Vec<f64>,
+    /// how close a drag has to come to a detent to stick to it
+    detent_snap: f64,
+    /// change from one key press
+    step: f64,
+    value: Box<Fn() -> Option<f64>>,
+    set: Box<FnMut(f64, &mut World)>,
+    focused: bool,
+}
+
+impl Slider {
+    pub fn new(
+        w: f64,
+        h: f64,
+        range: (f64, f64),
+        value: Box<Fn() -> Option<f64>>,
+        set: Box<FnMut(f64, &mut World)>,
+    ) -> Slider {
+        Slider {
+            w: w,
+            h: h,
+            min: range.0,
+            max: range.1,
+            detents: Vec::new(),
+            detent_snap: 0.0,
+            step: (range.1 - range.0) / 10.0,
+            value: value,
+            set: set,
+            focused: false,
+        }
+    }
+    pub fn detents(mut self, detents: Vec<f64>, snap: f64) -> Slider {
+        self.detents = detents;
+        self.detent_snap = snap;
+        self
+    }
+    pub fn step(mut self, step: f64) -> Slider {
+        self.step = step;
+        self
+    }
+    pub fn value(&self) -> Option<f64> {
+        (self.value)()
+    }
+    /// Move to `v`, kept in range and stuck to any detent it's near
+    pub fn set_value(&mut self, v: f64, w: &mut World) {
+        if self.value().is_none() {
+            return;
+        }
+        let v = v.max(self.min).min(self.max);
+        let v = self.detents
+            .iter()
+            .cloned()
+            .find(|&d| (v - d).abs() <= self.detent_snap)
+            .unwrap_or(v);
+        (self.set)(v, w)
+    }
+    fn to_y(&self, v: f64) -> f64 {
+        self.h * (self.max - v) / (self.max - self.min)
+    }
+    fn from_y(&self, y: f64) -> f64 {
+        self.max - (y / self.h) * (self.max - self.min)
+    }
+}
+
+impl Component for Slider {
+    fn bounding_box(&self) -> BoundingBox {
+        [0.0, 0.0, self.w, self.h]
+    }
+    fn drag_start(&mut self, _: f64, y: f64, w: &mut World) {
+        let v = self.from_y(y);
+        self.set_value(v, w)
+    }
+    fn drag(&mut self, _: f64, y: f64, w: &mut World) {
+        let v = self.from_y(y);
+        self.set_value(v, w)
+    }
+    fn set_focus(&mut self, focused: bool) {
+        self.focused = focused
+    }
+    fn focus_key(&mut self, b: &Button, w: &mut World) -> bool {
+        let step = match *b {
+            Button::Keyboard(Key::Up) => self.step,
+            Button::Keyboard(Key::Down) => -self.step,
+            _ => return false,
+        };
+        if let Some(v) = self.value() {
+            self.set_value(v + step, w);
+        }
+        true
+    }
+    fn draw(&mut self, ctx: Context, g: &mut G2d) {
+        let bounds = self.bounding_box();
+        let v = self.value();
+        let edge = if v.is_some() { EDGE } else { DEAD_EDGE };
+        frame(bounds, FACE, edge, self.focused, ctx, g);
+        let v = match v {
+            Some(v) => v,
+            None => return,
+        };
+        // filled from zero, or the nearer end if zero is out of range
+        let zero = self.to_y(0.0_f64.max(self.min).min(self.max));
+        let y = self.to_y(v);
+        let color = if v >= 0.0 { FORWARD_COLOR } else { REVERSE_COLOR };
+        Rectangle::new(color).draw(
+            [self.w * 0.25, f64::min(zero, y), self.w * 0.5, (zero - y).abs()],
+            &ctx.draw_state,
+            ctx.transform,
+            g,
+        );
+        for &d in self.detents.iter() {
+            let dy = self.to_y(d);
+            line(EDGE, 0.5, [0.0, dy, self.w, dy], ctx.transform, g);
+        }
+        Rectangle::new(EDGE).draw(
+            [0.0, y - 2.0, self.w, 4.0],
+            &ctx.draw_state,
+            ctx.transform,
+            g,
+        );
+    }
+}
+
+/// A column of options, at most one of them chosen
+pub struct ToggleGroup {
+    options: Vec<&'static str>,
+    w: f64,
+    /// the option to show as chosen
+    chosen: Box<Fn() -> Option<usize>>,
+    choose: Box<FnMut(usize, &mut World)>,
+    hovered: Option<usize>,
+    focused: bool,
+}
+
+impl ToggleGroup {
+    pub fn new(
+        options: Vec<&'static str>,
+        w: f64,
+        chosen: Box<Fn() -> Option<usize>>,
+        choose: Box<FnMut(usize, &mut World)>,
+    ) -> ToggleGroup {
+        ToggleGroup {
+            options: options,
+            w: w,
+            chosen: chosen,
+            choose: choose,
+            hovered: None,
+            focused: false,
+        }
+    }
+    fn option_at(&self, y: f64) -> Option<usize> {
+        let i = (y / LINE).floor();
+        if i >= 0.0 && (i as usize) < self.options.len() {
+            Some(i as usize)
+        } else {
+            None
+        }
+    }
+}
+
+impl Component for ToggleGroup {
+    fn bounding_box(&self) -> BoundingBox {
+        [0.0, 0.0, self.w, LINE * self.options.len() as f64]
+    }
+    fn click(&mut self, _: f64, y: f64, w: &mut World) {
+        if let Some(i) = self.option_at(y) {
+            (self.choose)(i, w)
+        }
+    }
+    fn pointer_move(&mut self, _: f64, y: f64, _: &mut World) {
+        self.hovered = self.option_at(y)
+    }
+    fn hover_leave(&mut self, _: &mut World) {
+        self.hovered = None
+    }
+    fn set_focus(&mut self, focused: bool) {
+        self.focused = focused
+    }
+    fn focus_key(&mut self, b: &Button, w: &mut World) -> bool {
+        let last = self.options.len().saturating_sub(1);
+        let next = match (*b, (self.chosen)()) {
+            (Button::Keyboard(Key::Up), Some(i)) => i.saturating_sub(1),
+            (Button::Keyboard(Key::Down), Some(i)) => (i + 1).min(last),
+            (Button::Keyboard(Key::Up), None) |
+            (Button::Keyboard(Key::Down), None) => 0,
+            _ => return false,
+        };
+        (self.choose)(next, w);
+        true
+    }
+    fn draw(&mut self, ctx: Context, g: &mut G2d) {
+        let chosen = (self.chosen)();
+        for (i, option) in self.options.iter().enumerate() {
+            let bounds = [0.0, i as f64 * LINE, self.w, LINE];
+            let face = if chosen == Some(i) {
+                CHOSEN
+            } else if self.hovered == Some(i) {
+                HOVER_FACE
+            } else {
+                FACE
+            };
+            frame(bounds, face, EDGE, false, ctx, g);
+            draw_centred(option, bounds, ctx, g);
+        }
+        if self.focused {
+            let b = self.bounding_box();
+            Rectangle::new_border(FOCUS_COLOR, 1.0).draw(
+                [b[0] + 1.0, b[1] + 1.0, b[2] - 2.0, b[3] - 2.0],
+                &ctx.draw_state,
+                ctx.transform,
+                g,
+            );
+        }
+    }
+}
+
+struct Child {
+    obj: Box<Component>,
+    /// where it is in the panel
+    bounds: BoundingBox,
+    /// higher is drawn later, and gets the pointer first
+    z: i32,
+}
+
+/// Widgets laid out by a `Layout`. The topmost widget under the pointer gets
+/// clicks, drags and scrolls; whichever was last pressed has keyboard focus.
+/// Panels are widgets too, so they nest.
+pub struct Panel {
+    layout: Layout,
+    children: Vec<Child>,
+    size: [f64; 2],
+    focus: Option<usize>,
+    /// the child the current drag started on
+    dragging: Option<usize>,
+    hovered: Option<usize>,
+}
+
+impl Panel {
+    pub fn new(layout: Layout) -> Panel {
+        Panel {
+            layout: layout,
+            children: Vec::new(),
+            size: [0.0; 2],
+            focus: None,
+            dragging: None,
+            hovered: None,
+        }
+    }
+    pub fn add<T: Component>(self, obj: T) -> Panel {
+        self.add_at(obj, 0)
+    }
+    /// Add a widget drawn above those with a lower `z`. Among equals, the
+    /// one added later is on top.
+    pub fn add_at<T: Component>(mut self, obj: T, z: i32) -> Panel {
+        self.children.push(Child {
+            obj: Box::new(obj),
+            bounds: [0.0; 4],
+            z: z,
+        });
+        self.arrange();
+        self
+    }
+    fn arrange(&mut self) {
+        let sizes: Vec<[f64; 2]> = self.children
+            .iter()
+            .map(|c| {
+                let bb = c.obj.bounding_box();
+                [bb[2], bb[3]]
+            })
+            .collect();
+        let (boxes, size) = self.layout.arrange(&sizes);
+        for (c, b) in self.children.iter_mut().zip(boxes) {
+            c.bounds = b;
+        }
+        self.size = size;
+    }
+    /// Children from the bottom of the pile to the top
+    fn by_z(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.children.len()).collect();
+        order.sort_by_key(|&i| self.children[i].z);
+        order
+    }
+    /// The topmost child under (x, y)
+    fn child_at(&self, x: f64, y: f64) -> Option<usize> {
+        self.by_z()
+            .into_iter()
+            .rev()
+            .find(|&i| collides_point(self.children[i].bounds, [x, y]))
+    }
+    /// (x, y) in the child's own coordinates
+    fn local(&self, i: usize, x: f64, y: f64) -> (f64, f64) {
+        let b = self.children[i].bounds;
+        (x - b[0], y - b[1])
+    }
+    fn focus_on(&mut self, i: Option<usize>) {
+        if self.focus == i {
+            return;
+        }
+        if let Some(old) = self.focus {
+            self.children[old].obj.set_focus(false);
+        }
+        if let Some(new) = i {
+            self.children[new].obj.set_focus(true);
+        }
+        self.focus = i;
+    }
+    fn hover(&mut self, i: Option<usize>, x: f64, y: f64, w: &mut World) {
+        if self.hovered == i {
+            return;
+        }
+        if let Some(old) = self.hovered {
+            self.children[old].obj.hover_leave(w);
+        }
+        if let Some(new) = i {
+            let (x, y) = self.local(new, x, y);
+            self.children[new].obj.hover_enter(x, y, w);
+        }
+        self.hovered = i;
+    }
+}
+
+impl Component for Panel {
+    fn bounding_box(&self) -> BoundingBox {
+        [0.0, 0.0, self.size[0], self.size[1]]
+    }
+    fn draw(&mut self, ctx: Context, g: &mut G2d) {
+        for i in self.by_z() {
+            let b = self.children[i].bounds;
+            self.children[i].obj.draw(ctx.trans(b[0], b[1]), g);
+        }
+    }
+    fn tick(&mut self, u: &UpdateArgs, w: &mut World) {
+        for c in self.children.iter_mut() {
+            c.obj.tick(u, w);
+        }
+    }
+    fn press(&mut self, b: &Button, w: &mut World) {
+        for c in self.children.iter_mut() {
+            c.obj.press(b, w);
+        }
+    }
+    fn release(&mut self, b: &Button, w: &mut World) {
+        for c in self.children.iter_mut() {
+            c.obj.release(b, w);
+        }
+    }
+    fn end_turn(&mut self, w: &mut World) {
+        for c in self.children.iter_mut() {
+            c.obj.end_turn(w);
+        }
+    }
+    fn action(&mut self, seat: usize, action: Action, w: &mut World) {
+        for c in self.children.iter_mut() {
+            c.obj.action(seat, action, w);
+        }
+    }
+    fn click(&mut self, x: f64, y: f64, w: &mut World) {
+        if let Some(i) = self.child_at(x, y) {
+            let (x, y) = self.local(i, x, y);
+            self.children[i].obj.click(x, y, w)
+        }
+    }
+    fn drag_start(&mut self, x: f64, y: f64, w: &mut World) {
+        let i = self.child_at(x, y);
+        self.focus_on(i);
+        self.dragging = i;
+        if let Some(i) = i {
+            let (x, y) = self.local(i, x, y);
+            self.children[i].obj.drag_start(x, y, w)
+        }
+    }
+    fn drag(&mut self, x: f64, y: f64, w: &mut World) {
+        if let Some(i) = self.dragging {
+            let (x, y) = self.local(i, x, y);
+            self.children[i].obj.drag(x, y, w)
+        }
+    }
+    fn drag_end(&mut self, x: f64, y: f64, w: &mut World) {
+        if let Some(i) = self.dragging.take() {
+            let (x, y) = self.local(i, x, y);
+            self.children[i].obj.drag_end(x, y, w)
+        }
+    }
+    fn pointer_move(&mut self, x: f64, y: f64, w: &mut World) {
+        let i = self.child_at(x, y);
+        self.hover(i, x, y, w);
+        if let Some(i) = i {
+            let (x, y) = self.local(i, x, y);
+            self.children[i].obj.pointer_move(x, y, w)
+        }
+    }
+    fn hover_leave(&mut self, w: &mut World) {
+        self.hover(None, 0.0, 0.0, w)
+    }
+    fn scroll(&mut self, x: f64, y: f64, dy: f64, w: &mut World) {
+        if let Some(i) = self.child_at(x, y) {
+            let (x, y) = self.local(i, x, y);
+            self.children[i].obj.scroll(x, y, dy, w)
+        }
+    }
+    fn context_click(&mut self, x: f64, y: f64, w: &mut World) {
+        if let Some(i) = self.child_at(x, y) {
+            let (x, y) = self.local(i, x, y);
+            self.children[i].obj.context_click(x, y, w)
+        }
+    }
+    fn set_focus(&mut self, focused: bool) {
+        // focus arrives through drag_start, which knows which child to give it
+        if !focused {
+            self.focus_on(None)
+        }
+    }
+    fn focus_key(&mut self, b: &Button, w: &mut World) -> bool {
+        match self.focus {
+            Some(i) => self.children[i].obj.focus_key(b, w),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use common::*;
+    use std::collections::LinkedList;
+    fn world() -> World {
+        World::new(LinkedList::new(), WorldState::new()).0
+    }
+    fn key(k: Key) -> Button {
+        Button::Keyboard(k)
+    }
+    /// A slider over -1 to 1, 10 by 100, whose value lives in `v`
+    fn slider(v: &Shared<f64>) -> Slider {
+        let (get, set) = (v.clone(), v.clone());
+        Slider::new(
+            10.0,
+            100.0,
+            (-1.0, 1.0),
+            Box::new(move || Some(get.get())),
+            Box::new(move |x: f64, _: &mut World| set.set(x)),
+        )
+    }
+    #[test]
+    fn child_at() {
+        let p = Panel::new(Layout::column(5.0))
+            .add(Label::new("A", 50.0))
+            .add(Label::new("B", 30.0));
+        assert_eq!(p.bounding_box(), [0.0, 0.0, 50.0, 45.0]);
+        assert_eq!(p.child_at(10.0, 10.0), Some(0));
+        assert_eq!(p.child_at(10.0, 30.0), Some(1));
+        // the gap between them, and beside the narrower one
+        assert_eq!(p.child_at(10.0, 22.0), None);
+        assert_eq!(p.child_at(40.0, 30.0), None);
+    }
+    #[test]
+    fn overlapping() {
+        // negative spacing piles each child partly over the one before
+        let p = Panel::new(Layout::column(-10.0))
+            .add_at(Label::new("A", 50.0), 1)
+            .add(Label::new("B", 50.0));
+        assert_eq!(p.by_z(), vec![1, 0]);
+        // the higher one wins where they overlap, though it went in first
+        assert_eq!(p.child_at(10.0, 15.0), Some(0));
+        assert_eq!(p.child_at(10.0, 25.0), Some(1));
+        // level with each other, the later one is on top
+        let p = Panel::new(Layout::column(-10.0))
+            .add(Label::new("A", 50.0))
+            .add(Label::new("B", 50.0));
+        assert_eq!(p.child_at(10.0, 15.0), Some(1));
+    }
+    #[test]
+    fn focus() {
+        let (a, b) = (shared(0.0), shared(0.0));
+        let mut w = world();
+        let mut p = Panel::new(Layout::row(0.0)).add(slider(&a)).add(slider(&b));
+        p.drag_start(5.0, 50.0, &mut w);
+        assert_eq!(p.focus, Some(0));
+        assert!(p.focus_key(&key(Key::Up), &mut w));
+        assert_eq!((a.get(), b.get()), (0.2, 0.0));
+        // pressing the other one takes the focus over
+        p.drag_start(15.0, 50.0, &mut w);
+        assert_eq!(p.focus, Some(1));
+        assert!(p.focus_key(&key(Key::Down), &mut w));
+        assert_eq!((a.get(), b.get()), (0.2, -0.2));
+        assert!(!p.focus_key(&key(Key::Left), &mut w));
+        p.set_focus(false);
+        assert_eq!(p.focus, None);
+        assert!(!p.focus_key(&key(Key::Up), &mut w));
+    }
+    #[test]
+    fn detents() {
+        let v = shared(0.5);
+        let mut w = world();
+        let mut s = slider(&v).detents(vec![0.0, 1.0], 0.05);
+        s.set_value(0.03, &mut w);
+        assert_eq!(v.get(), 0.0);
+        s.set_value(0.3, &mut w);
+        assert_eq!(v.get(), 0.3);
+        // dragged near the middle, it sticks to zero
+        s.drag_start(0.0, 48.0, &mut w);
+        assert_eq!(v.get(), 0.0);
+        // held in range, and stuck to the detent at the top
+        s.set_value(2.0, &mut w);
+        assert_eq!(v.get(), 1.0);
+        s.set_value(-3.0, &mut w);
+        assert_eq!(v.get(), -1.0);
+        // with nothing to control, nothing is set
+        let set = shared(false);
+        let hit = set.clone();
+        let mut dead = Slider::new(
+            10.0,
+            100.0,
+            (-1.0, 1.0),
+            Box::new(|| None),
+            Box::new(move |_: f64, _: &mut World| hit.set(true)),
+        );
+        dead.set_value(0.5, &mut w);
+        assert!(!set.get());
+    }
+    #[test]
+    fn toggle_keys() {
+        let chosen: Shared<Option<usize>> = shared(None);
+        let (get, set) = (chosen.clone(), chosen.clone());
+        let mut t = ToggleGroup::new(
+            vec!["A", "B", "C"],
+            50.0,
+            Box::new(move || get.get()),
+            Box::new(move |i: usize, _: &mut World| set.set(Some(i))),
+        );
+        let mut w = world();
+        assert!(t.focus_key(&key(Key::Down), &mut w));
+        assert_eq!(chosen.get(), Some(0));
+        for _ in 0..3 {
+            t.focus_key(&key(Key::Down), &mut w);
+        }
+        assert_eq!(chosen.get(), Some(2));
+        t.focus_key(&key(Key::Up), &mut w);
+        assert_eq!(chosen.get(), Some(1));
+        assert!(!t.focus_key(&key(Key::Left), &mut w));
+        t.click(5.0, 5.0, &mut w);
+        assert_eq!(chosen.get(), Some(0));
+    }
+}
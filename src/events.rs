@@ -0,0 +1,43 @@
+use std::collections::VecDeque;
+use std::collections::vec_deque::Iter;
+
+/// The most recent things that happened in a match, oldest first
+pub struct EventLog {
+    events: VecDeque<String>,
+    max: usize,
+}
+
+impl EventLog {
+    pub fn new(max: usize) -> EventLog {
+        EventLog {
+            events: VecDeque::with_capacity(max),
+            max: max,
+        }
+    }
+    pub fn push(&mut self, e: String) {
+        if self.events.len() == self.max {
+            self.events.pop_front();
+        }
+        self.events.push_back(e);
+    }
+    pub fn iter(&self) -> Iter<String> {
+        self.events.iter()
+    }
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn capped() {
+        let mut l = EventLog::new(2);
+        l.push("a".to_string());
+        l.push("b".to_string());
+        l.push("c".to_string());
+        assert_eq!(l.len(), 2);
+        assert_eq!(l.iter().cloned().collect::<Vec<_>>(), vec!["b", "c"]);
+    }
+}
@@ -0,0 +1,148 @@
+use graphics::{DrawState, Graphics, Rectangle};
+use graphics::math::Matrix2d;
+
+/// from the left of one letter to the left of the next, in font pixels
+pub const ADVANCE: f64 = 6.0;
+/// height of a letter, in font pixels
+pub const HEIGHT: f64 = 7.0;
+
+type Glyph = [&'static str; 7];
+
+/// drawn for anything the font doesn't have
+const MISSING: Glyph = ["#####", "#   #", "#   #", "#   #", "#   #", "#   #", "#####"];
+
+/// A 5x7 font of capitals, digits and a little punctuation.
+/// Lower case letters are drawn as capitals.
+const GLYPHS: [(char, Glyph); 57] = [
+    (' ', ["     ", "     ", "     ", "     ", "     ", "     ", "     "]),
+    ('A', [" ### ", "#   #", "#   #", "#####", "#   #", "#   #", "#   #"]),
+    ('B', ["#### ", "#   #", "#   #", "#### ", "#   #", "#   #", "#### "]),
+    ('C', [" ### ", "#   #", "#    ", "#    ", "#    ", "#   #", " ### "]),
+    ('D', ["#### ", "#   #", "#   #", "#   #", "#   #", "#   #", "#### "]),
+    ('E', ["#####", "#    ", "#    ", "#### ", "#    ", "#    ", "#####"]),
+    ('F', ["#####", "#    ", "#    ", "#### ", "#    ", "#    ", "#    "]),
+    ('G', [" ### ", "#   #", "#    ", "# ###", "#   #", "#   #", " ####"]),
+    ('H', ["#   #", "#   #", "#   #", "#####", "#   #", "#   #", "#   #"]),
+    ('I', [" ### ", "  #  ", "  #  ", "  #  ", "  #  ", "  #  ", " ### "]),
+    ('J', ["  ###", "   # ", "   # ", "   # ", "   # ", "#  # ", " ##  "]),
+    ('K', ["#   #", "#  # ", "# #  ", "##   ", "# #  ", "#  # ", "#   #"]),
+    ('L', ["#    ", "#    ", "#    ", "#    ", "#    ", "#    ", "#####"]),
+    ('M', ["#   #", "## ##", "# # #", "# # #", "#   #", "#   #", "#   #"]),
+    ('N', ["#   #", "#   #", "##  #", "# # #", "#  ##", "#   #", "#   #"]),
+    ('O', [" ### ", "#   #", "#   #", "#   #", "#   #", "#   #", " ### "]),
+    ('P', ["#### ", "#   #", "#   #", "#### ", "#    ", "#    ", "#    "]),
+    ('Q', [" ### ", "#   #", "#   #", "#   #", "# # #", "#  # ", " ## #"]),
+    ('R', ["#### ", "#   #", "#   #", "#### ", "# #  ", "#  # ", "#   #"]),
+    ('S', [" ####", "#    ", "#    ", " ### ", "    #", "    #", "#### "]),
+    ('T', ["#####", "  #  ", "  #  ", "  #  ", "  #  ", "  #  ", "  #  "]),
+    ('U', ["#   #", "#   #", "#   #", "#   #", "#   #", "#   #", " ### "]),
+    ('V', ["#   #", "#   #", "#   #", "#   #", "#   #", " # # ", "  #  "]),
+    ('W', ["#   #", "#   #", "#   #", "# # #", "# # #", "# # #", " # # "]),
+    ('X', ["#   #", "#   #", " # # ", "  #  ", " # # ", "#   #", "#   #"]),
+    ('Y', ["#   #", "#   #", " # # ", "  #  ", "  #  ", "  #  ", "  #  "]),
+    ('Z', ["#####", "    #", "   # ", "  #  ", " #   ", "#    ", "#####"]),
+    ('0', [" ### ", "#   #", "#  ##", "# # #", "##  #", "#   #", " ### "]),
+    ('1', ["  #  ", " ##  ", "  #  ", "  #  ", "  #  ", "  #  ", " ### "]),
+    ('2', [" ### ", "#   #", "    #", "   # ", "  #  ", " #   ", "#####"]),
+    ('3', ["#####", "   # ", "  #  ", "   # ", "    #", "#   #", " ### "]),
+    ('4', ["   # ", "  ## ", " # # ", "#  # ", "#####", "   # ", "   # "]),
+    ('5', ["#####", "#    ", "#### ", "    #", "    #", "#   #", " ### "]),
+    ('6', ["  ## ", " #   ", "#    ", "#### ", "#   #", "#   #", " ### "]),
+    ('7', ["#####", "    #", "   # ", "  #  ", " #   ", " #   ", " #   "]),
+    ('8', [" ### ", "#   #", "#   #", " ### ", "#   #", "#   #", " ### "]),
+    ('9', [" ### ", "#   #", "#   #", " ####", "    #", "   # ", " ##  "]),
+    ('.', ["     ", "     ", "     ", "     ", "     ", "     ", "  #  "]),
+    (',', ["     ", "     ", "     ", "     ", "     ", "  #  ", " #   "]),
+    (':', ["     ", "  #  ", "     ", "     ", "     ", "  #  ", "     "]),
+    ('-', ["     ", "     ", "     ", "#####", "     ", "     ", "     "]),
+    ('+', ["     ", "  #  ", "  #  ", "#####", "  #  ", "  #  ", "     "]),
+    ('=', ["     ", "     ", "#####", "     ", "#####", "     ", "     "]),
+    ('_', ["     ", "     ", "     ", "     ", "     ", "     ", "#####"]),
+    ('/', ["    #", "    #", "   # ", "  #  ", " #   ", "#    ", "#    "]),
+    ('%', ["##   ", "##  #", "   # ", "  #  ", " #   ", "#  ##", "   ##"]),
+    ('!', ["  #  ", "  #  ", "  #  ", "  #  ", "  #  ", "     ", "  #  "]),
+    ('?', [" ### ", "#   #", "    #", "   # ", "  #  ", "     ", "  #  "]),
+    ('\'', ["  #  ", "  #  ", "     ", "     ", "     ", "     ", "     "]),
+    ('(', ["   # ", "  #  ", " #   ", " #   ", " #   ", "  #  ", "   # "]),
+    (')', [" #   ", "  #  ", "   # ", "   # ", "   # ", "  #  ", " #   "]),
+    ('[', [" ### ", " #   ", " #   ", " #   ", " #   ", " #   ", " ### "]),
+    (']', [" ### ", "   # ", "   # ", "   # ", "   # ", "   # ", " ### "]),
+    ('<', ["    #", "   # ", "  #  ", " #   ", "  #  ", "   # ", "    #"]),
+    ('>', ["#    ", " #   ", "  #  ", "   # ", "  #  ", " #   ", "#    "]),
+    ('#', [" # # ", " # # ", "#####", " # # ", "#####", " # # ", " # # "]),
+    ('*', ["     ", "# # #", " ### ", "#####", " ### ", "# # #", "     "]),
+];
+
+fn glyph(c: char) -> Glyph {
+    let c = c.to_ascii_uppercase();
+    GLYPHS.iter().find(|g| g.0 == c).map_or(MISSING, |g| g.1)
+}
+
+/// Top left corners of the pixels lit to write `text`, in font pixels
+pub fn pixels(text: &str) -> Vec<[f64; 2]> {
+    let mut lit = Vec::new();
+    for (i, c) in text.chars().enumerate() {
+        for (y, row) in glyph(c).iter().enumerate() {
+            for (x, p) in row.chars().enumerate() {
+                if p == '#' {
+                    lit.push([i as f64 * ADVANCE + x as f64, y as f64]);
+                }
+            }
+        }
+    }
+    lit
+}
+
+/// How wide `text` is drawn at `size` screen pixels per font pixel
+pub fn text_width(text: &str, size: f64) -> f64 {
+    text.chars().count() as f64 * ADVANCE * size
+}
+
+/// Write `text` with its top left corner at `pos`, `size` screen pixels per
+/// font pixel. Any `Graphics` backend will do, not just piston's window.
+pub fn draw_text<G: Graphics>(
+    text: &str,
+    pos: [f64; 2],
+    size: f64,
+    color: [f32; 4],
+    draw_state: &DrawState,
+    transform: Matrix2d,
+    g: &mut G,
+) {
+    let pixel = Rectangle::new(color);
+    for p in pixels(text) {
+        pixel.draw(
+            [pos[0] + p[0] * size, pos[1] + p[1] * size, size, size],
+            draw_state,
+            transform,
+            g,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn glyphs_are_5x7() {
+        for &(c, g) in GLYPHS.iter() {
+            for row in g.iter() {
+                assert_eq!(row.len(), 5, "{:?}", c);
+                assert!(row.chars().all(|p| p == '#' || p == ' '), "{:?}", c);
+            }
+        }
+    }
+    #[test]
+    fn lower_case() {
+        assert_eq!(glyph('a'), glyph('A'));
+        assert_eq!(glyph('~'), MISSING);
+    }
+    #[test]
+    fn layout() {
+        assert_eq!(pixels("  "), vec![]);
+        assert_eq!(pixels("."), vec![[2.0, 6.0]]);
+        assert_eq!(pixels(" ."), vec![[ADVANCE + 2.0, 6.0]]);
+        assert_eq!(pixels("-").len(), 5);
+        assert_eq!(text_width("abc", 2.0), 3.0 * ADVANCE * 2.0);
+    }
+}
@@ -0,0 +1,234 @@
+use piston_window::*;
+use bindings::*;
+
+// axes and buttons as SDL's joystick API numbers an Xbox 360 style pad,
+// which is what sdl2_window passes on. The D-pad is a hat there, and
+// sdl2_window doesn't report hats, so it does nothing.
+pub const LEFT_X: u8 = 0;
+pub const LEFT_Y: u8 = 1;
+pub const LEFT_TRIGGER: u8 = 2;
+pub const RIGHT_X: u8 = 3;
+pub const RIGHT_Y: u8 = 4;
+pub const RIGHT_TRIGGER: u8 = 5;
+const AXES: usize = 6;
+
+pub const A: u8 = 0;
+pub const B: u8 = 1;
+pub const X: u8 = 2;
+pub const Y: u8 = 3;
+pub const LEFT_SHOULDER: u8 = 4;
+pub const RIGHT_SHOULDER: u8 = 5;
+pub const BACK: u8 = 6;
+pub const START: u8 = 7;
+
+/// sticks pushed less than this far are left alone
+const DEADZONE: f64 = 0.25;
+/// how far the fire trigger has to be pulled to shoot
+const TRIGGER_PULL: f64 = 0.5;
+
+/// What a seat is asked to do by its controller
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PadInput {
+    Action(Action),
+    /// the left stick, as a heading in radians
+    Heading(f64),
+    /// the right stick, as a unit vector to point the guns along
+    Aim([f64; 2]),
+    /// the right trigger, from 0 (released) to 1 (fully pulled)
+    Throttle(f64),
+}
+
+/// Controllers, the seats they're assigned to and what their buttons do.
+/// Left stick steers, right stick aims, right trigger is the throttle and
+/// left trigger fires.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gamepads {
+    /// controller id and the seat it plays at
+    seats: Vec<(i32, usize)>,
+    /// where each controller's axes last were
+    axes: Vec<(i32, [f64; AXES])>,
+    buttons: Vec<(u8, Action)>,
+}
+
+impl Gamepads {
+    pub fn new() -> Gamepads {
+        Gamepads {
+            seats: Vec::new(),
+            axes: Vec::new(),
+            buttons: vec![
+                (A, Action::FireFront),
+                (B, Action::FireBack),
+                (X, Action::Follow),
+                (Y, Action::AllStop),
+                (BACK, Action::Rebind),
+                (START, Action::EndTurn),
+                (LEFT_SHOULDER, Action::Slower),
+                (RIGHT_SHOULDER, Action::Faster),
+            ],
+        }
+    }
+    /// Give `controller` to the player at `seat`. Unassigned controllers are ignored.
+    pub fn assign(&mut self, controller: i32, seat: usize) {
+        self.seats.retain(|&(c, _)| c != controller);
+        self.seats.push((controller, seat));
+    }
+    pub fn seat(&self, controller: i32) -> Option<usize> {
+        self.seats.iter().find(|s| s.0 == controller).map(|s| s.1)
+    }
+    /// The actions `b` is bound to, and the seat its controller is at
+    pub fn actions(&self, b: &ControllerButton) -> Vec<(usize, Action)> {
+        let seat = match self.seat(b.id) {
+            Some(s) => s,
+            None => return Vec::new(),
+        };
+        self.buttons
+            .iter()
+            .filter(|&&(button, _)| button == b.button)
+            .map(|&(_, a)| (seat, a))
+            .collect()
+    }
+    /// What `i` asks of whichever seats, if it came from an assigned controller
+    pub fn input(&mut self, i: &Input) -> Vec<(usize, PadInput)> {
+        match *i {
+            Input::Press(Button::Controller(b)) => {
+                self.actions(&b)
+                    .into_iter()
+                    .map(|(seat, a)| (seat, PadInput::Action(a)))
+                    .collect()
+            }
+            Input::Move(Motion::ControllerAxis(a)) => {
+                match self.seat(a.id) {
+                    Some(seat) => {
+                        self.axis(a.id, a.axis, a.position)
+                            .into_iter()
+                            .map(|p| (seat, p))
+                            .collect()
+                    }
+                    None => Vec::new(),
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+    fn axis(&mut self, controller: i32, axis: u8, position: f64) -> Vec<PadInput> {
+        if axis as usize >= AXES {
+            return Vec::new();
+        }
+        if !self.axes.iter().any(|a| a.0 == controller) {
+            self.axes.push((controller, [0.0; AXES]));
+        }
+        let axes = &mut self.axes.iter_mut().find(|a| a.0 == controller).unwrap().1;
+        let was = axes[axis as usize];
+        axes[axis as usize] = position;
+        let stick = |x: u8, y: u8| {
+            let v = [axes[x as usize], axes[y as usize]];
+            if v[0].hypot(v[1]) < DEADZONE {
+                None
+            } else {
+                Some(v)
+            }
+        };
+        match axis {
+            LEFT_X | LEFT_Y => {
+                stick(LEFT_X, LEFT_Y)
+                    .map(|v| PadInput::Heading(v[1].atan2(v[0])))
+                    .into_iter()
+                    .collect()
+            }
+            RIGHT_X | RIGHT_Y => {
+                stick(RIGHT_X, RIGHT_Y)
+                    .map(|v| {
+                        let l = v[0].hypot(v[1]);
+                        PadInput::Aim([v[0] / l, v[1] / l])
+                    })
+                    .into_iter()
+                    .collect()
+            }
+            // some backends rest triggers at -1, which counts as released
+            RIGHT_TRIGGER => vec![PadInput::Throttle(position.max(0.0).min(1.0))],
+            LEFT_TRIGGER if was < TRIGGER_PULL && position >= TRIGGER_PULL => {
+                vec![
+                    PadInput::Action(Action::FireFront),
+                    PadInput::Action(Action::FireBack),
+                ]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::f64::consts::PI;
+    fn press(id: i32, button: u8) -> Input {
+        Input::Press(Button::Controller(ControllerButton {
+            id: id,
+            button: button,
+        }))
+    }
+    fn axis(id: i32, axis: u8, position: f64) -> Input {
+        Input::Move(Motion::ControllerAxis(ControllerAxisArgs {
+            id: id,
+            axis: axis,
+            position: position,
+        }))
+    }
+    fn pads() -> Gamepads {
+        let mut p = Gamepads::new();
+        p.assign(0, 0);
+        p.assign(1, 1);
+        p
+    }
+    #[test]
+    fn buttons() {
+        let mut p = pads();
+        assert_eq!(p.input(&press(0, A)), vec![(0, PadInput::Action(Action::FireFront))]);
+        assert_eq!(p.input(&press(1, START)), vec![(1, PadInput::Action(Action::EndTurn))]);
+        assert_eq!(p.input(&press(2, A)), vec![]);
+        assert_eq!(p.input(&press(0, 99)), vec![]);
+    }
+    #[test]
+    fn assignment() {
+        let mut p = pads();
+        p.assign(0, 1);
+        assert_eq!(p.seat(0), Some(1));
+        assert_eq!(p.seat(2), None);
+        assert_eq!(p.input(&press(0, B)), vec![(1, PadInput::Action(Action::FireBack))]);
+    }
+    #[test]
+    fn heading() {
+        let mut p = pads();
+        assert_eq!(p.input(&axis(0, LEFT_X, 0.1)), vec![]);
+        let turn = p.input(&axis(0, LEFT_Y, 1.0));
+        assert_eq!(turn.len(), 1);
+        match turn[0] {
+            (0, PadInput::Heading(h)) => assert!((h - 1.4711).abs() < 0.001),
+            x => panic!("{:?}", x),
+        }
+        p.input(&axis(0, LEFT_X, 0.0));
+        assert_eq!(p.input(&axis(0, LEFT_Y, -1.0)), vec![(0, PadInput::Heading(-PI / 2.0))]);
+        // the other controller's stick is its own
+        assert_eq!(p.input(&axis(1, LEFT_X, 0.1)), vec![]);
+    }
+    #[test]
+    fn aim() {
+        let mut p = pads();
+        assert_eq!(p.input(&axis(1, RIGHT_X, -0.5)), vec![(1, PadInput::Aim([-1.0, 0.0]))]);
+    }
+    #[test]
+    fn triggers() {
+        let mut p = pads();
+        assert_eq!(p.input(&axis(0, RIGHT_TRIGGER, -1.0)), vec![(0, PadInput::Throttle(0.0))]);
+        assert_eq!(p.input(&axis(0, RIGHT_TRIGGER, 0.5)), vec![(0, PadInput::Throttle(0.5))]);
+        let fire = vec![
+            (0, PadInput::Action(Action::FireFront)),
+            (0, PadInput::Action(Action::FireBack)),
+        ];
+        assert_eq!(p.input(&axis(0, LEFT_TRIGGER, 0.8)), fire);
+        // held down, it doesn't fire again until it's let go
+        assert_eq!(p.input(&axis(0, LEFT_TRIGGER, 0.9)), vec![]);
+        p.input(&axis(0, LEFT_TRIGGER, 0.0));
+        assert_eq!(p.input(&axis(0, LEFT_TRIGGER, 1.0)), fire);
+    }
+}
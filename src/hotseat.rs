@@ -0,0 +1,113 @@
+use team::*;
+
+/// Where a hot-seat turn has got to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// this player is entering orders, everyone else's ships are locked
+    Orders(PlayerId),
+    /// the screen is blanked until this player has the seat and is ready
+    Handover(PlayerId),
+    /// all orders are in and the turn plays out
+    Resolve,
+}
+
+/// Players taking turns at one machine, each entering orders
+/// the others don't get to see
+#[derive(Clone, Debug, PartialEq)]
+pub struct HotSeat {
+    players: Vec<PlayerId>,
+    phase: Phase,
+    turn: u32,
+}
+
+impl HotSeat {
+    /// `players` in the order they give orders. There must be at least one.
+    pub fn new(players: Vec<PlayerId>) -> HotSeat {
+        assert!(!players.is_empty(), "hot-seat needs players");
+        HotSeat {
+            phase: Phase::Orders(players[0]),
+            players: players,
+            turn: 1,
+        }
+    }
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+    /// The player whose ships take input, if anyone's do
+    pub fn active(&self) -> Option<PlayerId> {
+        match self.phase {
+            Phase::Orders(p) => Some(p),
+            _ => None,
+        }
+    }
+    /// The player giving orders has finished
+    pub fn done(&mut self) {
+        if let Phase::Orders(p) = self.phase {
+            let next = self.players
+                .iter()
+                .position(|&q| q == p)
+                .and_then(|i| self.players.get(i + 1));
+            self.phase = match next {
+                Some(&q) => Phase::Handover(q),
+                None => Phase::Resolve,
+            };
+        }
+    }
+    /// The next player has the seat
+    pub fn ready(&mut self) {
+        if let Phase::Handover(p) = self.phase {
+            self.phase = Phase::Orders(p);
+        }
+    }
+    /// The turn has played out; back to the first player
+    pub fn resolved(&mut self) {
+        if self.phase == Phase::Resolve {
+            self.turn += 1;
+            self.phase = Phase::Handover(self.players[0]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn full_turn() {
+        let mut h = HotSeat::new(vec![0, 1]);
+        assert_eq!(h.phase(), Phase::Orders(0));
+        assert_eq!(h.active(), Some(0));
+        h.done();
+        assert_eq!(h.phase(), Phase::Handover(1));
+        assert_eq!(h.active(), None);
+        h.ready();
+        assert_eq!(h.phase(), Phase::Orders(1));
+        h.done();
+        assert_eq!(h.phase(), Phase::Resolve);
+        h.resolved();
+        assert_eq!(h.phase(), Phase::Handover(0));
+        assert_eq!(h.turn(), 2);
+        h.ready();
+        assert_eq!(h.active(), Some(0));
+    }
+    #[test]
+    fn out_of_order() {
+        let mut h = HotSeat::new(vec![0, 1]);
+        h.ready();
+        h.resolved();
+        assert_eq!(h.phase(), Phase::Orders(0));
+        h.done();
+        h.done();
+        assert_eq!(h.phase(), Phase::Handover(1));
+    }
+    #[test]
+    fn single_player() {
+        let mut h = HotSeat::new(vec![3]);
+        h.done();
+        assert_eq!(h.phase(), Phase::Resolve);
+        h.resolved();
+        assert_eq!(h.phase(), Phase::Handover(3));
+    }
+}
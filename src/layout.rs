@@ -0,0 +1,86 @@
+/// Which way a panel lines its children up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Row,
+    Column,
+}
+
+/// How a panel places its children: one after another along `direction`,
+/// `spacing` apart, inside a margin of `padding`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Layout {
+    pub direction: Direction,
+    pub spacing: f64,
+    pub padding: f64,
+}
+
+impl Layout {
+    pub fn row(spacing: f64) -> Layout {
+        Layout {
+            direction: Direction::Row,
+            spacing: spacing,
+            padding: 0.0,
+        }
+    }
+    pub fn column(spacing: f64) -> Layout {
+        Layout {
+            direction: Direction::Column,
+            spacing: spacing,
+            padding: 0.0,
+        }
+    }
+    /// Where children with these sizes go, and how big that makes the whole
+    pub fn arrange(&self, sizes: &[[f64; 2]]) -> (Vec<[f64; 4]>, [f64; 2]) {
+        // along the direction, and across it
+        let (along, across) = match self.direction {
+            Direction::Row => (0, 1),
+            Direction::Column => (1, 0),
+        };
+        let mut at = self.padding;
+        let mut thickness: f64 = 0.0;
+        let mut boxes = Vec::new();
+        for (i, s) in sizes.iter().enumerate() {
+            if i > 0 {
+                at += self.spacing;
+            }
+            let mut b = [0.0; 4];
+            b[along] = at;
+            b[across] = self.padding;
+            b[2] = s[0];
+            b[3] = s[1];
+            boxes.push(b);
+            at += s[along];
+            thickness = thickness.max(s[across]);
+        }
+        let mut size = [0.0; 2];
+        size[along] = at + self.padding;
+        size[across] = thickness + 2.0 * self.padding;
+        (boxes, size)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn row() {
+        let (boxes, size) = Layout::row(5.0).arrange(&[[10.0, 20.0], [30.0, 10.0]]);
+        assert_eq!(boxes, vec![[0.0, 0.0, 10.0, 20.0], [15.0, 0.0, 30.0, 10.0]]);
+        assert_eq!(size, [45.0, 20.0]);
+    }
+    #[test]
+    fn column() {
+        let (boxes, size) = Layout::column(0.0).arrange(&[[10.0, 20.0], [30.0, 10.0]]);
+        assert_eq!(boxes, vec![[0.0, 0.0, 10.0, 20.0], [0.0, 20.0, 30.0, 10.0]]);
+        assert_eq!(size, [30.0, 30.0]);
+    }
+    #[test]
+    fn padding() {
+        let mut l = Layout::row(5.0);
+        l.padding = 2.0;
+        let (boxes, size) = l.arrange(&[[10.0, 10.0]]);
+        assert_eq!(boxes, vec![[2.0, 2.0, 10.0, 10.0]]);
+        assert_eq!(size, [14.0, 14.0]);
+        assert_eq!(l.arrange(&[]), (vec![], [4.0, 4.0]));
+    }
+}
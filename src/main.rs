@@ -3,6 +3,7 @@ extern crate graphics;
 extern crate float;
 extern crate piston;
 extern crate vecmath;
+extern crate sdl2_window;
 #[allow(unused_variables)]
 
 mod components;
@@ -10,21 +11,155 @@ mod universe;
 mod collisions;
 mod math;
 mod common;
+mod arc;
+mod turret;
+mod events;
+mod rng;
+mod physics;
+mod arena;
+mod camera;
+mod team;
+mod hotseat;
+mod layout;
+mod font;
+mod bindings;
+mod gamepad;
 
 use universe::*;
 use piston_window::*;
+use arena::*;
+use team::*;
+use hotseat::*;
+use layout::*;
+use bindings::*;
+use physics::*;
+use std::env;
+use std::fs;
+use vecmath::vec2_add;
 
 use components::ship::*;
+use components::minimap::*;
+use components::seat::*;
+use components::rebind::*;
+
+/// how far ships move each turn in a hot-seat game
+const HOTSEAT_TURN: f64 = 40.0;
+
+/// One player at the whole window: a big heading dial on the left,
+/// the other dials stacked next to it and the arena on the right
+const SOLO_LAYOUT: PanelLayout = PanelLayout {
+    pos: [0.0, 0.0],
+    heading_size: 500.0,
+    controls: Direction::Column,
+};
+const SOLO_VIEW: [f64; 4] = [600.0, 0.0, 600.0, 500.0];
+const SOLO_MINIMAP: [f64; 4] = [500.0, 425.0, 100.0, 75.0];
+
+/// Split-screen: each player gets half the window, with the dials in
+/// a strip along the top and the arena below
+const SPLIT_WIDTH: f64 = 600.0;
+fn split_layout(x: f64) -> PanelLayout {
+    PanelLayout {
+        pos: [x, 0.0],
+        heading_size: 200.0,
+        controls: Direction::Row,
+    }
+}
 
 fn main() {
-    let mut window: PistonWindow = WindowSettings::new("Hello Piston!", [1200, 500])
+    let mut window: GameWindow = WindowSettings::new("Hello Piston!", [1200, 500])
         .exit_on_esc(true)
+        .controllers(true)
         .build()
         .unwrap();
+    let hotseat = env::args().any(|a| a == "--hotseat");
+    let split = !hotseat && env::args().any(|a| a == "--split");
     let mut u = Universe::new();
-    let ship = Ship::new(&mut u, 1200.0, 500.0);
-    u.add(ship);
+    let arena = Arena::new(1400.0, 1000.0, Boundary::Clamp);
+    u.set_arena(arena);
+    let mut roster = Roster::new(Rules { friendly_fire: false });
+    let red = roster.add_team([1.0, 0.0, 0.0, 1.0]);
+    let blue = roster.add_team([0.0, 0.0, 1.0, 1.0]);
+    let p1 = roster.add_player(red);
+    let p2 = roster.add_player(blue);
+    u.set_roster(roster.clone());
+
+    let saved = match fs::read_to_string(CONFIG_FILE) {
+        Ok(text) => parse_config(&text).unwrap_or_else(|e| {
+            eprintln!("ignoring {}: {}", CONFIG_FILE, e);
+            Vec::new()
+        }),
+        Err(_) => Vec::new(),
+    };
+    // a seat's saved bindings, or its defaults
+    let bindings = |name: &str, defaults: fn() -> Bindings| {
+        saved
+            .iter()
+            .find(|s| s.0 == name)
+            .map_or_else(defaults, |s| s.1.clone())
+    };
+
+    // (seat, player sitting there, dials, minimap, view)
+    let mut seats = Vec::new();
+    if split {
+        let halves = [
+            (p1, "left", left_bindings as fn() -> Bindings),
+            (p2, "right", right_bindings),
+        ];
+        for (i, &(player, name, defaults)) in halves.iter().enumerate() {
+            let x = i as f64 * SPLIT_WIDTH;
+            let view = [x, 200.0, SPLIT_WIDTH, 300.0];
+            let seat = u.add_seat(name, view, bindings(name, defaults));
+            seats.push((seat, player, split_layout(x), [x + 410.0, 0.0, 100.0, 75.0], view));
+        }
+    } else {
+        let seat = u.add_seat("solo", SOLO_VIEW, bindings("solo", solo_bindings));
+        seats.push((seat, p1, SOLO_LAYOUT, SOLO_MINIMAP, SOLO_VIEW));
+    }
+    for (i, &(ref seat, player, layout, m, view)) in seats.iter().enumerate() {
+        seat.fleet.borrow_mut().set_active(player);
+        u.assign_pad(i as i32, seat);
+        // objects are added at the front, so the overlays go in first to be drawn last
+        let rebinder = make_rebinder(&mut u, view[0], view[1], seat);
+        u.add(rebinder);
+        let minimap = make_minimap(&mut u, m[0], m[1], m[2], m[3], seat.camera.clone());
+        u.add(minimap);
+        let panel = make_control_panel(&mut u, seat, layout);
+        u.add(panel);
+    }
+    if hotseat {
+        u.set_movement(Movement::PerTurn(HOTSEAT_TURN));
+        u.set_hotseat(HotSeat::new(vec![p1, p2]));
+    }
+
+    let cameras = u.cameras();
+    let centre = [arena.w / 2.0, arena.h / 2.0];
+    let ships = [
+        (CAPITAL, p1, [-100.0, 0.0]),
+        (FRIGATE, p1, [-60.0, 0.0]),
+        (CAPITAL, p2, [100.0, 0.0]),
+        (FRIGATE, p2, [60.0, 0.0]),
+    ];
+    for &(class, player, offset) in ships.iter() {
+        // a player without a seat of their own gives orders from the first one
+        let seat = seats
+            .iter()
+            .find(|s| s.1 == player)
+            .unwrap_or(&seats[0])
+            .0
+            .clone();
+        let ship = Ship::new(
+            &mut u,
+            class,
+            player,
+            roster.color(player),
+            vec2_add(centre, offset),
+            &seat,
+            &cameras,
+        );
+        u.add(ship);
+    }
     while let Some(event) = window.next() {
         u = u.handle_event(event, &mut window);
     }
-}
\ No newline at end of file
+}
@@ -17,6 +17,29 @@ pub fn vec2_max_add(a: Vector2<f64>, b: Vector2<f64>, max: f64) -> Vector2<f64>
     }
 }
 
+/// Rotate `v` by `a` radians, clockwise on screen (y grows downwards),
+/// matching `rot_rad` in piston's transforms
+pub fn vec2_rotate(v: Vector2<f64>, a: f64) -> Vector2<f64> {
+    let (s, c) = a.sin_cos();
+    [v[0] * c - v[1] * s, v[0] * s + v[1] * c]
+}
+
+/// Remove whatever part of `v` heads into a surface with outward normal `n`.
+/// Motion away from or along the surface is left alone.
+pub fn vec2_cancel_into(v: Vector2<f64>, n: Vector2<f64>) -> Vector2<f64> {
+    let d = vec2_dot(v, n);
+    if d < 0.0 {
+        vec2_sub(v, vec2_scale(n, d))
+    } else {
+        v
+    }
+}
+
+/// Round `a` to the nearest multiple of `step`
+pub fn snap_angle(a: f64, step: f64) -> f64 {
+    (a / step).round() * step
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -30,4 +53,24 @@ mod test {
         //ug floats
         //assert_eq!(vec2_angle([1.0, 1.0]),f64::_90() / 2.0);
     }
+    #[test]
+    fn rotate() {
+        let v = vec2_rotate([0.0, 1.0], vec2_angle([1.0, 0.0]));
+        assert!((v[0] - 1.0).abs() < 1e-9 && v[1].abs() < 1e-9);
+        let v = vec2_rotate([1.0, 0.0], ::std::f64::consts::PI);
+        assert!((v[0] + 1.0).abs() < 1e-9 && v[1].abs() < 1e-9);
+    }
+    #[test]
+    fn snap() {
+        let step = ::std::f64::consts::PI / 4.0;
+        assert_eq!(snap_angle(0.3, step), 0.0);
+        assert_eq!(snap_angle(0.5, step), step);
+        assert_eq!(snap_angle(-1.6, step), -2.0 * step);
+    }
+    #[test]
+    fn cancel_into() {
+        assert_eq!(vec2_cancel_into([-1.0, 1.0], [1.0, 0.0]), [0.0, 1.0]);
+        assert_eq!(vec2_cancel_into([1.0, 1.0], [1.0, 0.0]), [1.0, 1.0]);
+        assert_eq!(vec2_cancel_into([0.0, -2.0], [0.0, 1.0]), [0.0, 0.0]);
+    }
 }
@@ -0,0 +1,181 @@
+use vecmath::*;
+use math::*;
+use arc::*;
+
+/// How a ship class moves. Distances are in arena units, times in seconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Engine {
+    /// acceleration along the heading at full throttle
+    pub thrust: f64,
+    pub max_speed: f64,
+    /// fraction of velocity lost per second, 0 for none
+    pub drag: f64,
+    /// most radians per second the hull can turn
+    pub turn_rate: f64,
+    /// radians per second per second, None to start and stop turning instantly
+    pub turn_accel: Option<f64>,
+}
+
+/// Whether ships move continuously or in discrete turns
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Movement {
+    RealTime,
+    /// at the end of each turn every ship moves exactly this far
+    PerTurn(f64),
+}
+
+/// Advance a body by `dt` seconds, thrusting along the unit vector `heading`.
+/// `throttle` runs from -1 (full reverse) to 1. Returns the new (pos, vel).
+pub fn integrate(
+    pos: Vector2<f64>,
+    vel: Vector2<f64>,
+    heading: Vector2<f64>,
+    throttle: f64,
+    engine: Engine,
+    dt: f64,
+) -> (Vector2<f64>, Vector2<f64>) {
+    let accel = vec2_scale(heading, engine.thrust * throttle * dt);
+    let vel = vec2_max_add(vel, accel, engine.max_speed);
+    let vel = vec2_scale(vel, f64::max(1.0 - engine.drag * dt, 0.0));
+    (vec2_add(pos, vec2_scale(vel, dt)), vel)
+}
+
+/// Turn from `current` towards `target` by at most `max_step` radians
+pub fn turn_toward(current: f64, target: f64, max_step: f64) -> f64 {
+    let d = angle_diff(current, target);
+    angle_normalize(current + d.max(-max_step).min(max_step))
+}
+
+/// Turn `heading` towards `target` over `dt` seconds, limited by the engine's
+/// turn rate and, if it has one, angular acceleration. `spin` is the current
+/// angular velocity. Returns the new (heading, spin).
+pub fn steer(heading: f64, spin: f64, target: f64, engine: Engine, dt: f64) -> (f64, f64) {
+    let d = angle_diff(heading, target);
+    let spin = match engine.turn_accel {
+        None => (d / dt).max(-engine.turn_rate).min(engine.turn_rate),
+        Some(accel) => {
+            // fastest spin we can still brake from before reaching the target
+            let brake = (2.0 * accel * d.abs()).sqrt();
+            let want = d.signum() * f64::min(engine.turn_rate, brake);
+            let dv = (want - spin).max(-accel * dt).min(accel * dt);
+            spin + dv
+        }
+    };
+    let step = spin * dt;
+    if step.abs() >= d.abs() && step * d >= 0.0 {
+        (angle_normalize(target), 0.0)
+    } else {
+        (angle_normalize(heading + step), spin)
+    }
+}
+
+fn unit_or_zero(v: Vector2<f64>) -> Vector2<f64> {
+    if vec2_len(v) == 0.0 {
+        v
+    } else {
+        vec2_normalized(v)
+    }
+}
+
+/// The turn-based velocity change: add the heading to the current course and
+/// normalize, giving the new course as a unit vector (or zero if stopped).
+/// With no throttle the course is left alone.
+pub fn turn_velocity(vel: Vector2<f64>, heading: Vector2<f64>, throttle: f64) -> Vector2<f64> {
+    let course = unit_or_zero(vel);
+    unit_or_zero(vec2_add(course, vec2_scale(heading, throttle)))
+}
+
+/// Velocity after `dt` seconds of a held push along the unit vector `dir`.
+/// `push` runs from -1 (astern) to 1, in fractions of `max_speed` gained per
+/// second, and the push never takes the speed past `max_speed`.
+pub fn held_thrust(
+    vel: Vector2<f64>,
+    dir: Vector2<f64>,
+    push: f64,
+    max_speed: f64,
+    dt: f64,
+) -> Vector2<f64> {
+    vec2_max_add(vel, vec2_scale(dir, push * max_speed * dt), max_speed)
+}
+
+/// Move exactly `n` units along `vel`. A body that isn't moving stays put.
+pub fn move_exactly(pos: Vector2<f64>, vel: Vector2<f64>, n: f64) -> Vector2<f64> {
+    if vec2_len(vel) == 0.0 {
+        pos
+    } else {
+        vec2_add(pos, vec2_scale(vec2_normalized(vel), n))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    const ENGINE: Engine = Engine {
+        thrust: 10.0,
+        max_speed: 5.0,
+        drag: 0.0,
+        turn_rate: 1.0,
+        turn_accel: None,
+    };
+    #[test]
+    fn thrust() {
+        let (p, v) = integrate([0.0, 0.0], [0.0, 0.0], [1.0, 0.0], 1.0, ENGINE, 0.1);
+        assert_eq!(v, [1.0, 0.0]);
+        assert_eq!(p, [0.1, 0.0]);
+    }
+    #[test]
+    fn max_speed() {
+        let (_, v) = integrate([0.0, 0.0], [4.0, 0.0], [1.0, 0.0], 1.0, ENGINE, 1.0);
+        assert_eq!(v, [5.0, 0.0]);
+    }
+    #[test]
+    fn drag() {
+        let e = Engine { drag: 0.5, ..ENGINE };
+        let (_, v) = integrate([0.0, 0.0], [4.0, 0.0], [1.0, 0.0], 0.0, e, 1.0);
+        assert_eq!(v, [2.0, 0.0]);
+    }
+    #[test]
+    fn held() {
+        let v = held_thrust([0.0, 0.0], [1.0, 0.0], 1.0, 10.0, 0.5);
+        assert_eq!(v, [5.0, 0.0]);
+        // however the time is cut up, a held push adds up the same
+        let v = held_thrust([0.0, 0.0], [1.0, 0.0], 1.0, 10.0, 0.25);
+        assert_eq!(held_thrust(v, [1.0, 0.0], 1.0, 10.0, 0.25), [5.0, 0.0]);
+        assert_eq!(held_thrust([0.0, 0.0], [0.0, 1.0], -0.5, 10.0, 0.5), [0.0, -2.5]);
+        assert_eq!(held_thrust([9.0, 0.0], [1.0, 0.0], 1.0, 10.0, 1.0), [10.0, 0.0]);
+    }
+    #[test]
+    fn exactly() {
+        assert_eq!(move_exactly([1.0, 1.0], [0.0, 10.0], 3.0), [1.0, 4.0]);
+        assert_eq!(move_exactly([1.0, 1.0], [0.0, 0.0], 3.0), [1.0, 1.0]);
+    }
+    #[test]
+    fn turn_rate() {
+        let (h, _) = steer(0.0, 0.0, 3.0, ENGINE, 0.5);
+        assert_eq!(h, 0.5);
+        let (h, s) = steer(0.0, 0.0, 0.1, ENGINE, 0.5);
+        assert_eq!((h, s), (0.1, 0.0));
+        assert_eq!(turn_toward(0.0, 1.0, 0.25), 0.25);
+        assert_eq!(turn_toward(0.0, -1.0, 0.25), -0.25);
+    }
+    #[test]
+    fn turn_inertia() {
+        let e = Engine {
+            turn_accel: Some(1.0),
+            ..ENGINE
+        };
+        let (h, s) = steer(0.0, 0.0, 3.0, e, 0.5);
+        assert_eq!(s, 0.5);
+        assert_eq!(h, 0.25);
+        // spinning hard right next to the target: has to slow down
+        let (_, s) = steer(0.0, 1.0, 0.01, e, 0.1);
+        assert!(s < 1.0);
+    }
+    #[test]
+    fn turn() {
+        assert_eq!(turn_velocity([3.0, 0.0], [0.0, 1.0], 0.0), [1.0, 0.0]);
+        assert_eq!(turn_velocity([-1.0, 0.0], [1.0, 0.0], 1.0), [0.0, 0.0]);
+        assert_eq!(turn_velocity([1.0, 0.0], [1.0, 0.0], 1.0), [1.0, 0.0]);
+        assert_eq!(turn_velocity([0.0, 0.0], [0.0, 1.0], 1.0), [0.0, 1.0]);
+    }
+}
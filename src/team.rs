@@ -0,0 +1,116 @@
+pub type PlayerId = u32;
+pub type TeamId = u32;
+
+/// colour of anything nobody owns
+pub const NEUTRAL_COLOR: [f32; 4] = [0.4, 0.4, 0.4, 1.0];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Team {
+    pub id: TeamId,
+    /// everything the team's players own is drawn in this
+    pub color: [f32; 4],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Player {
+    pub id: PlayerId,
+    pub team: TeamId,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rules {
+    /// can shots and rams hurt ships on the shooter's own team?
+    pub friendly_fire: bool,
+}
+
+/// Who is playing, on which side
+#[derive(Clone, Debug, PartialEq)]
+pub struct Roster {
+    teams: Vec<Team>,
+    players: Vec<Player>,
+    pub rules: Rules,
+}
+
+impl Roster {
+    pub fn new(rules: Rules) -> Roster {
+        Roster {
+            teams: Vec::new(),
+            players: Vec::new(),
+            rules: rules,
+        }
+    }
+    pub fn add_team(&mut self, color: [f32; 4]) -> TeamId {
+        let id = self.teams.len() as TeamId;
+        self.teams.push(Team {
+            id: id,
+            color: color,
+        });
+        id
+    }
+    pub fn add_player(&mut self, team: TeamId) -> PlayerId {
+        let id = self.players.len() as PlayerId;
+        self.players.push(Player {
+            id: id,
+            team: team,
+        });
+        id
+    }
+    pub fn team(&self, player: PlayerId) -> Option<TeamId> {
+        self.players.iter().find(|p| p.id == player).map(|p| p.team)
+    }
+    /// The colour of the player's team
+    pub fn color(&self, player: PlayerId) -> [f32; 4] {
+        self.team(player)
+            .and_then(|t| self.teams.iter().find(|team| team.id == t))
+            .map_or(NEUTRAL_COLOR, |t| t.color)
+    }
+    /// Are both owned, and by players on the same team?
+    pub fn allied(&self, a: Option<PlayerId>, b: Option<PlayerId>) -> bool {
+        match (a.and_then(|a| self.team(a)), b.and_then(|b| self.team(b))) {
+            (Some(x), Some(y)) => x == y,
+            _ => false,
+        }
+    }
+    /// Can something owned by `shooter` hurt something owned by `target`?
+    pub fn may_damage(&self, shooter: Option<PlayerId>, target: Option<PlayerId>) -> bool {
+        self.rules.friendly_fire || !self.allied(shooter, target)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn roster(friendly_fire: bool) -> (Roster, PlayerId, PlayerId, PlayerId) {
+        let mut r = Roster::new(Rules { friendly_fire: friendly_fire });
+        let red = r.add_team([1.0, 0.0, 0.0, 1.0]);
+        let blue = r.add_team([0.0, 0.0, 1.0, 1.0]);
+        let a = r.add_player(red);
+        let b = r.add_player(red);
+        let c = r.add_player(blue);
+        (r, a, b, c)
+    }
+    #[test]
+    fn colors() {
+        let (r, a, _, c) = roster(false);
+        assert_eq!(r.color(a), [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(r.color(c), [0.0, 0.0, 1.0, 1.0]);
+        assert_eq!(r.color(7), NEUTRAL_COLOR);
+    }
+    #[test]
+    fn allies() {
+        let (r, a, b, c) = roster(false);
+        assert!(r.allied(Some(a), Some(b)));
+        assert!(!r.allied(Some(a), Some(c)));
+        assert!(!r.allied(Some(a), None));
+        assert!(!r.allied(None, None));
+    }
+    #[test]
+    fn friendly_fire() {
+        let (r, a, b, c) = roster(false);
+        assert!(!r.may_damage(Some(a), Some(b)));
+        assert!(r.may_damage(Some(a), Some(c)));
+        assert!(r.may_damage(None, Some(a)));
+        let (r, a, b, _) = roster(true);
+        assert!(r.may_damage(Some(a), Some(b)));
+    }
+}
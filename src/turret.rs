@@ -0,0 +1,138 @@
+/// What a gun mount fires
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WeaponKind {
+    /// plain shells that fly straight
+    Cannon,
+    /// slow projectiles that steer towards the nearest ship
+    Missile,
+    /// instant beam that hits the first thing in its path
+    Laser,
+    /// shells that burst at a set distance, damaging everything nearby
+    Flak,
+}
+
+/// Static description of how a gun behaves
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GunSpec {
+    pub weapon: WeaponKind,
+    /// seconds between shots
+    pub reload: f64,
+    /// rounds carried, None for unlimited
+    pub ammo: Option<u32>,
+    /// heat added by every shot
+    pub heat_per_shot: f64,
+    /// heat shed per second
+    pub cooling: f64,
+    /// heat at which the gun overheats and locks until it has cooled
+    pub max_heat: f64,
+}
+
+/// The current condition of one gun
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GunState {
+    pub spec: GunSpec,
+    pub reload_left: f64,
+    pub ammo: Option<u32>,
+    pub heat: f64,
+    pub overheated: bool,
+}
+
+impl GunState {
+    pub fn new(spec: GunSpec) -> GunState {
+        GunState {
+            spec: spec,
+            reload_left: 0.0,
+            ammo: spec.ammo,
+            heat: 0.0,
+            overheated: false,
+        }
+    }
+    pub fn ready(&self) -> bool {
+        self.reload_left <= 0.0 && !self.overheated && self.ammo != Some(0)
+    }
+    /// Try to fire, returns false (and changes nothing) if the gun isn't ready
+    pub fn fire(&mut self) -> bool {
+        if !self.ready() {
+            return false;
+        }
+        self.reload_left = self.spec.reload;
+        self.ammo = self.ammo.map(|a| a - 1);
+        self.heat += self.spec.heat_per_shot;
+        if self.heat >= self.spec.max_heat {
+            self.overheated = true;
+        }
+        true
+    }
+    pub fn tick(&mut self, dt: f64) {
+        self.reload_left = f64::max(self.reload_left - dt, 0.0);
+        self.heat = f64::max(self.heat - self.spec.cooling * dt, 0.0);
+        if self.heat <= 0.0 {
+            self.overheated = false;
+        }
+    }
+    /// 0 when loaded, 1 just after firing
+    pub fn reload_fraction(&self) -> f64 {
+        if self.spec.reload <= 0.0 {
+            0.0
+        } else {
+            self.reload_left / self.spec.reload
+        }
+    }
+    pub fn heat_fraction(&self) -> f64 {
+        if self.spec.max_heat <= 0.0 {
+            0.0
+        } else {
+            f64::min(self.heat / self.spec.max_heat, 1.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn spec() -> GunSpec {
+        GunSpec {
+            weapon: WeaponKind::Cannon,
+            reload: 1.0,
+            ammo: Some(2),
+            heat_per_shot: 1.0,
+            cooling: 1.0,
+            max_heat: 10.0,
+        }
+    }
+    #[test]
+    fn reload() {
+        let mut g = GunState::new(spec());
+        assert!(g.fire());
+        assert!(!g.fire());
+        g.tick(0.5);
+        assert!(!g.ready());
+        g.tick(0.5);
+        assert!(g.fire());
+    }
+    #[test]
+    fn ammo() {
+        let mut g = GunState::new(GunSpec { reload: 0.0, ..spec() });
+        assert!(g.fire());
+        assert!(g.fire());
+        assert!(!g.fire());
+        assert_eq!(g.ammo, Some(0));
+    }
+    #[test]
+    fn overheat() {
+        let mut g = GunState::new(GunSpec {
+            reload: 0.0,
+            ammo: None,
+            heat_per_shot: 5.0,
+            ..spec()
+        });
+        assert!(g.fire());
+        assert!(g.fire());
+        assert!(g.overheated);
+        assert!(!g.fire());
+        g.tick(5.0);
+        assert!(!g.fire());
+        g.tick(5.0);
+        assert!(g.fire());
+    }
+}
@@ -1,16 +1,69 @@
 
 use components::*;
+use physics::*;
+use arena::*;
+use camera::*;
+use collisions::*;
+use common::*;
+use components::fleet::*;
+use components::seat::*;
+use team::*;
+use hotseat::*;
+use font::*;
+use bindings::*;
+use gamepad::*;
+use components::throttle::MAX_THROTTLE;
+use vecmath::{vec2_add, vec2_scale};
 use piston_window::*;
+use sdl2_window::Sdl2Window;
 use std::collections::linked_list::*;
+use std::fs;
 use piston_window::Input::*;
 use piston_window::Button::*;
 
+/// how much one notch of the mouse wheel zooms
+const ZOOM_STEP: f64 = 1.1;
+/// how far the pointer has to move with a button down before it's a drag
+/// (a selection box, or a pan) rather than a click, in pixels
+const BOX_THRESHOLD: f64 = 4.0;
+const BOX_COLOR: [f32; 4] = [1.0, 0.8, 0.0, 1.0];
+/// how close to a ship a click has to be to select it, in arena units
+const SELECT_RADIUS: f64 = 15.0;
+/// what the screen shows while a hot-seat is changing hands
+const HANDOVER_BACKGROUND: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+const HANDOVER_CARD: f64 = 100.0;
+/// screen pixels per font pixel in the HUD
+const HUD_TEXT: f64 = 2.0;
+const HUD_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+/// how many of the latest events the HUD shows
+const HUD_EVENTS: usize = 3;
+/// how far ahead of the ship a stick aims the guns, in arena units
+const AIM_RANGE: f64 = 1000.0;
+
+/// The window the game runs in. It's on SDL2 rather than piston_window's
+/// default glutin backend, which doesn't report controllers.
+pub type GameWindow = PistonWindow<Sdl2Window>;
+
 pub struct Universe {
     components: LinkedList<GameObject>,
     mouse_down: bool,
     mouse_x: f64,
     mouse_y: f64,
-    id_counter: UUID,
+    /// the seat being panned and where the right button last was
+    pan_from: Option<(usize, [f64; 2])>,
+    /// the seat being box selected in and where the left button went down
+    box_from: Option<(usize, [f64; 2])>,
+    /// where the right button went down; it's a context click if it comes up nearby
+    context_from: Option<[f64; 2]>,
+    seats: Vec<Seat>,
+    pads: Gamepads,
+    /// the cameras of all the seats
+    cameras: Cameras,
+    /// players taking turns at the keyboard, if it's a hot-seat game
+    hotseat: Option<HotSeat>,
+    /// the button that took the seat at the last handover, until it comes up
+    taken_with: Option<Button>,
+    state: WorldState,
 }
 impl Universe {
     pub fn new() -> Universe {
@@ -19,43 +72,366 @@ impl Universe {
             mouse_down: false,
             mouse_x: 0.0,
             mouse_y: 0.0,
-            id_counter: 0,
+            pan_from: None,
+            box_from: None,
+            context_from: None,
+            seats: Vec::new(),
+            pads: Gamepads::new(),
+            cameras: shared_ref(Vec::new()),
+            hotseat: None,
+            taken_with: None,
+            state: WorldState::new(),
         }
     }
     pub fn add(&mut self, b: GameObject) {
         self.components.push_front(b);
     }
-    pub fn handle_event(self, i: Input, window: &mut PistonWindow) -> Self {
+    pub fn set_movement(&mut self, m: Movement) {
+        self.state.movement = m;
+    }
+    pub fn set_arena(&mut self, a: Arena) {
+        self.state.arena = a;
+    }
+    pub fn set_roster(&mut self, r: Roster) {
+        self.state.roster = r;
+    }
+    /// Play hot-seat: each player's orders are entered in turn with the
+    /// screen blanked in between, then the turn resolves
+    pub fn set_hotseat(&mut self, h: HotSeat) {
+        if let Some(p) = h.active() {
+            self.set_active(p);
+        }
+        self.hotseat = Some(h);
+    }
+    /// Add a place at the game looking at the arena through `view` (on screen)
+    /// and driven with `bindings`. Its ships are whichever are registered with
+    /// its fleet; they take orders from the fleet's active player.
+    pub fn add_seat(&mut self, name: &'static str, view: BoundingBox, bindings: Bindings) -> Seat {
+        let arena = self.state.arena;
+        let seat = Seat {
+            id: self.seats.len(),
+            name: name,
+            camera: shared(Camera::new(view, [arena.w / 2.0, arena.h / 2.0])),
+            fleet: shared_ref(Fleet::new()),
+            bindings: shared_ref(bindings),
+        };
+        self.cameras.borrow_mut().push(seat.camera.clone());
+        self.seats.push(seat.clone());
+        seat
+    }
+    /// Let the controller numbered `controller` play at `seat`
+    pub fn assign_pad(&mut self, controller: i32, seat: &Seat) {
+        self.pads.assign(controller, seat.id);
+    }
+    /// Every camera arena objects are drawn through
+    pub fn cameras(&self) -> Cameras {
+        self.cameras.clone()
+    }
+    fn set_active(&self, p: PlayerId) {
+        for s in self.seats.iter() {
+            s.fleet.borrow_mut().set_active(p);
+        }
+    }
+    /// The seat whose view of the arena is at `p` on screen
+    fn seat_at(&self, p: [f64; 2]) -> Option<usize> {
+        self.seats.iter().position(|s| collides_point(s.camera.get().view, p))
+    }
+    /// Keep track of the buttons down. The window losing focus lets go of
+    /// them all, since their releases won't arrive.
+    fn track_held(&mut self, i: &Input) {
+        match *i {
+            Press(b) if !self.state.held.contains(&b) => self.state.held.push(b),
+            Release(b) => self.state.held.retain(|&h| h != b),
+            Focus(false) => self.state.held.clear(),
+            _ => (),
+        }
+    }
+    pub fn handle_event(mut self, i: Input, window: &mut GameWindow) -> Self {
+        // kept up to date even while input is being ignored, so nothing sticks down
+        self.track_held(&i);
+        // while the seat is changing hands nothing runs, and the next key
+        // or click means the next player is ready
+        if let Some(p) = self.handover() {
+            return match i {
+                Press(b) => {
+                    self.taken_with = Some(b);
+                    self.take_seat()
+                }
+                Render(_) => self.draw_handover(p, i, window),
+                _ => self,
+            };
+        }
+        // letting go of it isn't the new player's first click
         match i {
+            Press(b) | Release(b) if self.taken_with == Some(b) => {
+                self.taken_with = None;
+                if let Release(_) = i {
+                    return self;
+                }
+            }
+            _ => (),
+        }
+        let mut next = match i {
             Press(button) => self.handle_press(button),
             Release(button) => self.handle_release(button),
             Move(m) => self.handle_move(m),
             Render(_) => self.handle_draw(i, window),
-            Update(u) => self.each(|c, w| c.tick(&u, w)),
+            Update(u) => {
+                self.state.holding = self.holding();
+                self.each(|c, w| c.tick(&u, w))
+            }
             _ => self,
+        };
+        if next.state.rebound {
+            next.state.rebound = false;
+            next.save_bindings();
         }
+        next
     }
-    fn handle_draw(self, i: Input, window: &mut PistonWindow) -> Self {
+    fn handle_draw(self, i: Input, window: &mut GameWindow) -> Self {
+        let band = self.selection_box();
+        let hud = self.hud();
         let mut y = None;
         window.draw_2d(&i, |ctx, g| {
             clear([1.0; 4], g);
             y = Some(self.each(|c, _| c.draw(ctx, g)));
+            if let Some((_, b)) = band {
+                Rectangle::new_border(BOX_COLOR, 1.0).draw(b, &ctx.draw_state, ctx.transform, g);
+            }
+            // down the top right corner of the window
+            let right = ctx.get_view_size()[0];
+            for (n, &(ref text, color)) in hud.iter().enumerate() {
+                let pos = [
+                    right - text_width(text, HUD_TEXT) - HUD_TEXT * 2.0,
+                    (n as f64 + 0.5) * (HEIGHT + 2.0) * HUD_TEXT,
+                ];
+                draw_text(text, pos, HUD_TEXT, color, &ctx.draw_state, ctx.transform, g);
+            }
         });
         y.unwrap()
     }
+    /// What the HUD says: whose turn it is, and the latest events
+    fn hud(&self) -> Vec<(String, [f32; 4])> {
+        let mut lines = Vec::new();
+        match self.hotseat.as_ref() {
+            Some(h) => {
+                if let Some(p) = h.active() {
+                    let text = format!("turn {} - player {} to order", h.turn(), p + 1);
+                    lines.push((text, self.state.roster.color(p)));
+                }
+            }
+            None => {
+                if self.state.movement != Movement::RealTime {
+                    lines.push(("return ends the turn".to_string(), HUD_COLOR));
+                }
+            }
+        }
+        let events = self.state.events.len();
+        for e in self.state.events.iter().skip(events.saturating_sub(HUD_EVENTS)) {
+            lines.push((e.clone(), HUD_COLOR));
+        }
+        lines
+    }
+    /// The player the hot-seat is being passed to, while the screen is blanked
+    fn handover(&self) -> Option<PlayerId> {
+        match self.hotseat.as_ref().map(|h| h.phase()) {
+            Some(Phase::Handover(p)) => Some(p),
+            _ => None,
+        }
+    }
+    fn draw_handover(self, p: PlayerId, i: Input, window: &mut GameWindow) -> Self {
+        let color = self.state.roster.color(p);
+        window.draw_2d(&i, |ctx, g| {
+            clear(HANDOVER_BACKGROUND, g);
+            let size = ctx.get_view_size();
+            let card = [
+                (size[0] - HANDOVER_CARD) / 2.0,
+                (size[1] - HANDOVER_CARD) / 2.0,
+                HANDOVER_CARD,
+                HANDOVER_CARD,
+            ];
+            Rectangle::new(color).draw(card, &ctx.draw_state, ctx.transform, g);
+            let text = format!("player {} - press any key", p + 1);
+            let pos = [
+                (size[0] - text_width(&text, HUD_TEXT)) / 2.0,
+                card[1] + HANDOVER_CARD + HEIGHT * HUD_TEXT,
+            ];
+            draw_text(&text, pos, HUD_TEXT, color, &ctx.draw_state, ctx.transform, g);
+        });
+        self
+    }
+    fn take_seat(mut self) -> Self {
+        if let Some(h) = self.hotseat.as_mut() {
+            h.ready();
+        }
+        self.next_phase()
+    }
+    /// The player giving orders (or everyone, outside hot-seat) is done
+    fn end_orders(mut self) -> Self {
+        match self.hotseat.as_mut() {
+            Some(h) => h.done(),
+            None => return self.each(|c, w| c.end_turn(w)),
+        }
+        if self.mouse_down {
+            let (x, y) = (self.mouse_x, self.mouse_y);
+            self = self.each(|c, w| c.drag_end(x, y, w));
+        }
+        self.mouse_down = false;
+        self.pan_from = None;
+        self.box_from = None;
+        self.context_from = None;
+        self.next_phase()
+    }
+    /// Catch up with the hot-seat's new phase
+    fn next_phase(self) -> Self {
+        match self.hotseat.as_ref().map(|h| h.phase()) {
+            Some(Phase::Orders(p)) => {
+                self.set_active(p);
+                self
+            }
+            Some(Phase::Resolve) => {
+                let mut u = self.each(|c, w| c.end_turn(w));
+                if let Some(h) = u.hotseat.as_mut() {
+                    u.state.events.push(format!("turn {} resolved", h.turn()));
+                    h.resolved();
+                }
+                u
+            }
+            _ => self,
+        }
+    }
+    /// The seat a box is being dragged out in to select ships, and the box in
+    /// screen coordinates, once the drag has gone far enough to count
+    fn selection_box(&self) -> Option<(usize, BoundingBox)> {
+        self.box_from.and_then(|(seat, from)| {
+            let to = [self.mouse_x, self.mouse_y];
+            let b = [
+                f64::min(from[0], to[0]),
+                f64::min(from[1], to[1]),
+                (from[0] - to[0]).abs(),
+                (from[1] - to[1]).abs(),
+            ];
+            if f64::max(b[2], b[3]) >= BOX_THRESHOLD {
+                Some((seat, b))
+            } else {
+                None
+            }
+        })
+    }
+    /// Write every seat's bindings to the config file
+    fn save_bindings(&mut self) {
+        let text = {
+            let bindings: Vec<_> = self.seats
+                .iter()
+                .map(|s| (s.name, s.bindings.borrow()))
+                .collect();
+            let sections: Vec<_> = bindings.iter().map(|&(n, ref b)| (n, &**b)).collect();
+            write_config(&sections)
+        };
+        if let Err(e) = fs::write(CONFIG_FILE, text) {
+            self.state.events.push(format!("couldn't save {}: {}", CONFIG_FILE, e));
+        }
+    }
+    /// Deliver the actions `b` is bound to on each seat
+    fn dispatch(mut self, b: Button) -> Self {
+        let actions: Vec<(usize, Action)> = self.seats
+            .iter()
+            .flat_map(|s| s.bindings.borrow().actions(&b).into_iter().map(move |a| (s.id, a)))
+            .collect();
+        for (seat, a) in actions {
+            self = self.act(seat, a);
+        }
+        self
+    }
+    /// What every seat's held buttons are bound to
+    fn holding(&self) -> Vec<(usize, Action)> {
+        let mut holding = Vec::new();
+        for b in self.state.held.iter() {
+            for s in self.seats.iter() {
+                holding.extend(s.bindings.borrow().actions(b).into_iter().map(|a| (s.id, a)));
+            }
+            if let Controller(c) = *b {
+                holding.extend(self.pads.actions(&c));
+            }
+        }
+        holding
+    }
+    fn act(self, seat: usize, a: Action) -> Self {
+        match a {
+            Action::EndTurn if self.state.movement != Movement::RealTime => self.end_orders(),
+            Action::EndTurn => self,
+            _ => self.each(|c, w| c.action(seat, a, w)),
+        }
+    }
+    /// Deliver what a controller asks of its seat
+    fn handle_pad(mut self, i: Input) -> Self {
+        for (s, p) in self.pads.input(&i) {
+            if let PadInput::Action(a) = p {
+                self = self.act(s, a);
+                continue;
+            }
+            let fleet = match self.seats.get(s) {
+                Some(seat) => seat.fleet.borrow(),
+                None => continue,
+            };
+            let order = match p {
+                PadInput::Heading(h) => Order::Heading(h),
+                PadInput::Throttle(t) => Order::Throttle(t * MAX_THROTTLE),
+                PadInput::Aim(dir) => {
+                    match fleet.selected() {
+                        Some(ship) => {
+                            let pos = ship.pose.get().pos;
+                            Order::Target(vec2_add(pos, vec2_scale(dir, AIM_RANGE)))
+                        }
+                        None => continue,
+                    }
+                }
+                PadInput::Action(_) => continue,
+            };
+            // sticks send a stream of orders, so refusals would flood the log
+            fleet.order(order);
+        }
+        self
+    }
     fn handle_press(mut self, b: Button) -> Self {
+        // a seat waiting for a button to rebind takes it
+        if self.seats.iter().any(|s| s.bindings.borrow_mut().capture(b)) {
+            self.state.rebound = true;
+            return self;
+        }
+        // then the focused widget, if it wants the key and no seat has it bound.
+        // Focus isn't per seat, so bound keys always go to their actions.
+        let bound = self.seats.iter().any(|s| !s.bindings.borrow().actions(&b).is_empty());
+        if let Keyboard(_) = b {
+            let mut used = false;
+            if !bound {
+                self = self.each(|c, w| used = c.focus_key(&b, w) || used);
+            }
+            if used {
+                return self;
+            }
+        }
+        self = self.dispatch(b);
         match b {
             Keyboard(_) => self.each(|c, w| c.press(&b, w)),
             Mouse(m) => {
                 match m {
                     MouseButton::Left => {
                         self.mouse_down = true;
-                        self
+                        let at = [self.mouse_x, self.mouse_y];
+                        self.box_from = self.seat_at(at).map(|s| (s, at));
+                        self.each(|c, w| c.drag_start(at[0], at[1], w))
+                    }
+                    MouseButton::Right => {
+                        let at = [self.mouse_x, self.mouse_y];
+                        self.pan_from = self.seat_at(at).map(|s| (s, at));
+                        self.context_from = Some(at);
+                        self.each(|c, w| c.press(&b, w))
                     }
                     _ => self.each(|c, w| c.press(&b, w)),
                 }
             }
-            Controller(_) => self,
+            Controller(_) => self.handle_pad(Press(b)),
         }
     }
     fn handle_release(self, b: Button) -> Self {
@@ -68,12 +444,54 @@ impl Universe {
     fn handle_mouse_release(mut self, m: MouseButton) -> Self {
         match m {
             MouseButton::Left => {
+                let (x, y) = (self.mouse_x, self.mouse_y);
+                if self.mouse_down {
+                    self = self.each(|c, w| c.drag_end(x, y, w));
+                }
                 self.mouse_down = false;
-                let x = self.mouse_x;
-                let y = self.mouse_y;
+                let band = self.selection_box();
+                self.box_from = None;
+                if let Some((s, b)) = band {
+                    let seat = &self.seats[s];
+                    let cam = seat.camera.get();
+                    let from = cam.screen_to_world([b[0], b[1]]);
+                    let to = cam.screen_to_world([b[0] + b[2], b[1] + b[3]]);
+                    let area = [from[0], from[1], to[0] - from[0], to[1] - from[1]];
+                    seat.fleet.borrow_mut().select_in(area);
+                    return self;
+                }
+                if let Some(s) = self.seat_at([x, y]) {
+                    let seat = &self.seats[s];
+                    let p = seat.camera.get().screen_to_world([x, y]);
+                    seat.fleet.borrow_mut().select_at(p, SELECT_RADIUS);
+                }
                 self.each(|c, w| c.click(x, y, w))
             }
-            _ => self.each(|c, w| c.press(&Mouse(m), w)),
+            MouseButton::Middle => {
+                let at = [self.mouse_x, self.mouse_y];
+                if let Some(s) = self.seat_at(at) {
+                    let seat = &self.seats[s];
+                    let order = Order::Target(seat.camera.get().screen_to_world(at));
+                    let refused = seat.fleet.borrow().order(order);
+                    for id in refused {
+                        self.state.events.push(refusal(id, order));
+                    }
+                }
+                self.each(|c, w| c.release(&Mouse(m), w))
+            }
+            MouseButton::Right => {
+                self.pan_from = None;
+                let (x, y) = (self.mouse_x, self.mouse_y);
+                let from = self.context_from.take();
+                let u = self.each(|c, w| c.release(&Mouse(m), w));
+                match from {
+                    Some(f) if f64::max((x - f[0]).abs(), (y - f[1]).abs()) < BOX_THRESHOLD => {
+                        u.each(|c, w| c.context_click(x, y, w))
+                    }
+                    _ => u,
+                }
+            }
+            _ => self.each(|c, w| c.release(&Mouse(m), w)),
         }
     }
     fn handle_move(mut self, m: Motion) -> Self {
@@ -81,12 +499,25 @@ impl Universe {
             Motion::MouseCursor(x, y) => {
                 self.mouse_x = x;
                 self.mouse_y = y;
+                if let Some((s, from)) = self.pan_from {
+                    update(&self.seats[s].camera, |c| c.pan([x - from[0], y - from[1]]));
+                    self.pan_from = Some((s, [x, y]));
+                }
+                self = self.each(|c, w| c.pointer_move(x, y, w));
                 if self.mouse_down {
                     self.each(|c, w| c.drag(x, y, w))
                 } else {
                     self
                 }
             }
+            Motion::MouseScroll(_, dy) => {
+                let at = [self.mouse_x, self.mouse_y];
+                if let Some(s) = self.seat_at(at) {
+                    update(&self.seats[s].camera, |c| c.zoom_at(at, ZOOM_STEP.powf(dy)));
+                }
+                self.each(|c, w| c.scroll(at[0], at[1], dy, w))
+            }
+            Motion::ControllerAxis(_) => self.handle_pad(Move(m)),
             _ => self,
         }
     }
@@ -99,37 +530,114 @@ impl Universe {
             mouse_down,
             mouse_x,
             mouse_y,
-            id_counter,
+            pan_from,
+            box_from,
+            context_from,
+            seats,
+            pads,
+            cameras,
+            hotseat,
+            taken_with,
+            state,
         } = self;
 
-        let (mut w, mut oc) = World::new(components,id_counter);
+        let (mut w, mut oc) = World::new(components, state);
         loop {
             match oc {
                 Some(mut c) => {
+                    let damage = w.take_damage(c.id);
+                    if damage > 0 {
+                        c.hit(damage, &mut w);
+                    }
                     f(&mut c, &mut w);
                     oc = w.rotate(c);
                 }
                 None => break,
             }
         }
-        let (c, ids) = w.complete();
+        let (c, state) = w.complete();
         Universe {
             components: c,
             mouse_down: mouse_down,
             mouse_x: mouse_x,
             mouse_y: mouse_y,
-            id_counter: ids,
+            pan_from: pan_from,
+            box_from: box_from,
+            context_from: context_from,
+            seats: seats,
+            pads: pads,
+            cameras: cameras,
+            hotseat: hotseat,
+            taken_with: taken_with,
+            state: state,
         }
     }
 }
 
 impl GameObjectFactory for Universe {
     fn new_gameobject(&mut self) -> GameObject {
-        let id = self.id_counter;
-        self.id_counter += 1;
+        let id = self.state.id_counter;
+        self.state.id_counter += 1;
         GameObject {
             components: LinkedList::new(),
             id,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use components::ship::*;
+    #[test]
+    fn holding() {
+        let mut u = Universe::new();
+        u.add_seat("left", [0.0; 4], left_bindings());
+        let right = u.add_seat("right", [0.0; 4], right_bindings());
+        u.assign_pad(0, &right);
+        let w = Keyboard(Key::W);
+        u.track_held(&Press(w));
+        u.track_held(&Press(Keyboard(Key::Up)));
+        assert_eq!(u.holding(), vec![(0, Action::Thrust), (1, Action::Thrust)]);
+        u.track_held(&Release(w));
+        assert_eq!(u.holding(), vec![(1, Action::Thrust)]);
+        let pad = Controller(ControllerButton {
+            id: 0,
+            button: B,
+        });
+        u.track_held(&Press(pad));
+        assert_eq!(u.holding(), vec![(1, Action::Thrust), (1, Action::FireBack)]);
+        // nothing stays held once the window has lost focus
+        u.track_held(&Focus(false));
+        assert!(u.holding().is_empty());
+    }
+    #[test]
+    fn ramming() {
+        let mut u = Universe::new();
+        let mut roster = Roster::new(Rules { friendly_fire: false });
+        let (red, blue) = (roster.add_team([1.0; 4]), roster.add_team([1.0; 4]));
+        let (p1, p2) = (roster.add_player(red), roster.add_player(blue));
+        u.set_roster(roster);
+        let cameras = u.cameras();
+        // each ship selected at a seat of its own, one above the other and touching
+        let mut seats = Vec::new();
+        for &(name, player, pos) in [("a", p1, [100.0, 100.0]), ("b", p2, [100.0, 125.0])].iter() {
+            let seat = u.add_seat(name, [0.0; 4], Bindings::new());
+            seat.fleet.borrow_mut().set_active(player);
+            let ship = Ship::new(&mut u, FRIGATE, player, [1.0; 4], pos, &seat, &cameras);
+            u.add(ship);
+            seats.push(seat);
+        }
+        // the top one is heading down into the bottom one, which sits still
+        seats[1].fleet.borrow().order(Order::Throttle(0.0));
+        let health = |s: &Seat| s.fleet.borrow().selected().unwrap().pose.get().health;
+        let (a, b) = (health(&seats[0]), health(&seats[1]));
+        let tick = UpdateArgs { dt: 0.1 };
+        // the knocks are dealt in the first tick and taken in the second
+        for _ in 0..2 {
+            u = u.each(|c, w| c.tick(&tick, w));
+        }
+        // both take the knock, however they were moving
+        assert_eq!((health(&seats[0]), health(&seats[1])), (a - 1, b - 1));
+    }
+}